@@ -0,0 +1,108 @@
+//! Blocks made by the user.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+
+use crate::block_material::BlockMaterial;
+use crate::layers::Layer;
+use crate::shapes::ShapeKind;
+
+/// A block made by the user, with the shape, size and material it was made with.
+#[derive(Component, Copy, Clone, Debug)]
+pub struct Block {
+    pub shape: ShapeKind,
+    pub size: Vec3,
+    pub material: BlockMaterial,
+}
+
+/// Identifies a block across despawning and respawning it, e.g. when undoing and redoing.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BlockId(pub u64);
+
+impl BlockId {
+    /// Returns an id no other block has been given yet.
+    pub fn next() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Sent when the user makes a new block.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct BlockSpawned(pub Entity);
+
+/// Sent when the user moves a block by hand, with where it was before and after.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct BlockMoved {
+    pub id: BlockId,
+    pub from: Transform,
+    pub to: Transform,
+}
+
+/// Sent when the user repaints a block, with how it looked before and after.
+#[derive(Event, Clone, Debug)]
+pub struct BlockRepainted {
+    pub id: BlockId,
+    pub from: Handle<StandardMaterial>,
+    pub to: Handle<StandardMaterial>,
+}
+
+/// Sent when the user deletes a block, with everything needed to bring it back.
+#[derive(Event, Clone, Debug)]
+pub struct BlockDeleted(pub BlockSnapshot);
+
+/// Everything needed to bring a block back exactly as it was.
+#[derive(Clone, Debug)]
+pub struct BlockSnapshot {
+    pub id: BlockId,
+    pub block: Block,
+    pub transform: Transform,
+    pub material: Handle<StandardMaterial>,
+}
+
+impl BlockSnapshot {
+    /// Spawns the block again with the same id.
+    pub fn respawn(&self, commands: &mut Commands, meshes: &mut Assets<Mesh>) -> Entity {
+        let entity = spawn_block(
+            commands,
+            meshes,
+            self.material.clone(),
+            self.block,
+            self.transform,
+        );
+        commands.entity(entity).insert(self.id);
+        entity
+    }
+}
+
+/// Spawns a dynamic `block` at `transform`, ignoring its scale. `material` is how it looks, usually
+/// made with [`BlockMaterial::standard_material`].
+pub fn spawn_block(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    material: Handle<StandardMaterial>,
+    block: Block,
+    transform: Transform,
+) -> Entity {
+    commands
+        .spawn((
+            PbrBundle {
+                mesh: meshes.add(block.shape.mesh(block.size)),
+                material,
+                transform: transform.with_scale(Vec3::ONE),
+                ..default()
+            },
+            RigidBody::Dynamic,
+            LinearVelocity(Vec3::new(0.0, 0.0, 0.0)),
+            block.shape.collider(block.size),
+            Layer::Blocks.collision_layers(),
+            block.material.friction(),
+            block.material.restitution(),
+            block.material.density(),
+            block,
+            BlockId::next(),
+        ))
+        .id()
+}
@@ -0,0 +1,156 @@
+//! What blocks are made of.
+//!
+//! A [`BlockMaterial`] decides how a block slides, bounces and weighs, how it looks and how it
+//! sounds when it hits something, so picking "ice" before making a block gives a slippery, glassy,
+//! light clinking block in one go.
+
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+pub enum BlockMaterial {
+    #[default]
+    Wood,
+    Rubber,
+    Ice,
+    Metal,
+    Foam,
+}
+
+/// The material new blocks are made of.
+#[derive(Resource, Deref, DerefMut, Copy, Clone, Default)]
+pub struct SelectedMaterial(pub BlockMaterial);
+
+/// How a material sounds when it hits something.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ImpactSound {
+    /// A few takes of the material being hit, one is picked at random for every hit.
+    pub paths: [&'static str; 3],
+    /// Playback speed, higher sounds brighter and harder.
+    pub speed: f32,
+    /// Multiplier on the impact volume.
+    pub volume: f32,
+}
+
+impl BlockMaterial {
+    pub const ALL: [BlockMaterial; 5] = [
+        BlockMaterial::Wood,
+        BlockMaterial::Rubber,
+        BlockMaterial::Ice,
+        BlockMaterial::Metal,
+        BlockMaterial::Foam,
+    ];
+
+    pub fn friction(self) -> Friction {
+        Friction::new(match self {
+            BlockMaterial::Wood => 0.5,
+            BlockMaterial::Rubber => 0.9,
+            BlockMaterial::Ice => 0.03,
+            BlockMaterial::Metal => 0.4,
+            BlockMaterial::Foam => 0.7,
+        })
+    }
+
+    pub fn restitution(self) -> Restitution {
+        Restitution::new(match self {
+            BlockMaterial::Wood => 0.3,
+            BlockMaterial::Rubber => 0.8,
+            BlockMaterial::Ice => 0.1,
+            BlockMaterial::Metal => 0.2,
+            BlockMaterial::Foam => 0.05,
+        })
+    }
+
+    /// Density in kilograms per cubic meter, so bigger blocks get heavier.
+    pub fn density(self) -> ColliderDensity {
+        ColliderDensity(match self {
+            BlockMaterial::Wood => 600.0,
+            BlockMaterial::Rubber => 1100.0,
+            BlockMaterial::Ice => 917.0,
+            BlockMaterial::Metal => 7800.0,
+            BlockMaterial::Foam => 30.0,
+        })
+    }
+
+    /// A [`StandardMaterial`] in `color` with the surface look of the material.
+    pub fn standard_material(self, color: Color) -> StandardMaterial {
+        let mut material = StandardMaterial::from(color);
+        match self {
+            BlockMaterial::Wood => {
+                material.perceptual_roughness = 0.8;
+            }
+            BlockMaterial::Rubber => {
+                material.perceptual_roughness = 0.95;
+                material.reflectance = 0.3;
+            }
+            BlockMaterial::Ice => {
+                material.base_color.set_a(0.7);
+                material.alpha_mode = AlphaMode::Blend;
+                material.perceptual_roughness = 0.05;
+                material.reflectance = 0.9;
+            }
+            BlockMaterial::Metal => {
+                material.metallic = 1.0;
+                material.perceptual_roughness = 0.3;
+            }
+            BlockMaterial::Foam => {
+                material.perceptual_roughness = 1.0;
+                material.reflectance = 0.1;
+            }
+        }
+        material
+    }
+
+    pub fn impact_sound(self) -> ImpactSound {
+        let (paths, volume) = match self {
+            BlockMaterial::Wood => (
+                [
+                    "embedded://impacts/wood-1.wav",
+                    "embedded://impacts/wood-2.wav",
+                    "embedded://impacts/wood-3.wav",
+                ],
+                1.0,
+            ),
+            BlockMaterial::Rubber => (
+                [
+                    "embedded://impacts/rubber-1.wav",
+                    "embedded://impacts/rubber-2.wav",
+                    "embedded://impacts/rubber-3.wav",
+                ],
+                0.6,
+            ),
+            BlockMaterial::Ice => (
+                [
+                    "embedded://impacts/ice-1.wav",
+                    "embedded://impacts/ice-2.wav",
+                    "embedded://impacts/ice-3.wav",
+                ],
+                0.8,
+            ),
+            BlockMaterial::Metal => (
+                [
+                    "embedded://impacts/metal-1.wav",
+                    "embedded://impacts/metal-2.wav",
+                    "embedded://impacts/metal-3.wav",
+                ],
+                1.2,
+            ),
+            BlockMaterial::Foam => (
+                [
+                    "embedded://impacts/foam-1.wav",
+                    "embedded://impacts/foam-2.wav",
+                    "embedded://impacts/foam-3.wav",
+                ],
+                0.2,
+            ),
+        };
+        ImpactSound {
+            paths,
+            speed: 1.0,
+            volume,
+        }
+    }
+}
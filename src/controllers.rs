@@ -0,0 +1,269 @@
+//! Motion controllers standing in for tracked hands.
+//!
+//! While a controller is in use it stands in for its hand in [`HandsState`]: the index tip sits
+//! just ahead of the aim pose, the palm on the grip pose, pulling the trigger pinches and
+//! squeezing the grip closes the hand, so anything reading [`HandsState`] works the same with
+//! either input. Trigger pinches are reported as [`PinchEvent`]s like tracked ones. The tracked
+//! hand bones are left alone.
+//!
+//! [`Controllers`] holds the controller input, [`OpenXrControllerPlugin`] fills it from OpenXR
+//! actions.
+
+use bevy::prelude::*;
+use bevy_xr::actions::ActionType;
+use bevy_xr::hands::HandBone;
+use bevy_xr_utils::tracking_utils::{
+    TrackingUtilitiesPlugin, XrTrackedLeftGrip, XrTrackedRightGrip,
+};
+use bevy_xr_utils::xr_utils_actions::{
+    ActiveSet, XRUtilsAction, XRUtilsActionSet, XRUtilsActionState, XRUtilsActionsPlugin,
+    XRUtilsBinding,
+};
+
+use crate::hands::{Hand, HandInputSet, HandState, HandsPlugin, HandsState};
+use crate::pinch::PinchEvent;
+
+pub struct ControllerPlugin;
+
+impl Plugin for ControllerPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<HandsPlugin>() {
+            app.add_plugins(HandsPlugin);
+        }
+        app.init_resource::<ControllerSettings>();
+        app.init_resource::<Controllers>();
+        app.configure_sets(Update, ControllerSet.in_set(HandInputSet));
+        app.add_systems(Update, feed_hands_from_controllers.in_set(ControllerSet));
+    }
+}
+
+/// Writes the controllers into [`HandsState`], fill [`Controllers`] before this set.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ControllerSet;
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct ControllerSettings {
+    /// Distance in meters from the aim pose along its forward direction to the index tip.
+    pub tip_offset: f32,
+    /// How far the trigger has to be pulled to start pinching.
+    pub pinch_trigger: f32,
+    /// A pinch is let go once the trigger is released below this.
+    pub release_trigger: f32,
+    /// The aim pose relative to the grip pose, for runtimes only reporting the grip.
+    pub aim_from_grip: Transform,
+}
+
+impl Default for ControllerSettings {
+    fn default() -> Self {
+        Self {
+            tip_offset: 0.05,
+            pinch_trigger: 0.7,
+            release_trigger: 0.4,
+            aim_from_grip: Transform::from_xyz(0.0, 0.0, -0.05)
+                .with_rotation(Quat::from_rotation_x(-40f32.to_radians())),
+        }
+    }
+}
+
+/// Input of one motion controller.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ControllerState {
+    /// Pose of the handle, oriented like the palm holding it.
+    pub grip: Transform,
+    /// Pose pointing out of the front of the controller.
+    pub aim: Transform,
+    /// How far the trigger is pulled, from 0 to 1.
+    pub trigger: f32,
+    /// How hard the grip is squeezed, from 0 to 1.
+    pub squeeze: f32,
+}
+
+impl ControllerState {
+    /// The hand holding this controller, `pinching` while its trigger is held.
+    pub fn hand_state(&self, pinching: bool, settings: &ControllerSettings) -> HandState {
+        let index_tip = Transform::from_translation(
+            self.aim.translation + *self.aim.forward() * settings.tip_offset,
+        )
+        .with_rotation(self.aim.rotation);
+        let mut joints = [None; 26];
+        joints[HandBone::Palm as usize] = Some(self.grip);
+        joints[HandBone::Wrist as usize] = Some(self.grip * Transform::from_xyz(0.0, 0.0, 0.08));
+        joints[HandBone::IndexTip as usize] = Some(index_tip);
+        HandState {
+            joints,
+            tracked: true,
+            pinching,
+            pinch_strength: self.trigger.clamp(0.0, 1.0),
+            grab_strength: self.squeeze.clamp(0.0, 1.0),
+            // Grip poses are oriented like OpenXR palms, +Y on the back of the hand
+            palm_normal: Some(*self.grip.down()),
+            pinch_point: Some(index_tip.with_rotation(self.grip.rotation)),
+        }
+    }
+}
+
+/// The controller held in each hand, left then right, `None` while it isn't in use.
+#[derive(Resource, Copy, Clone, Debug, Default)]
+pub struct Controllers(pub [Option<ControllerState>; 2]);
+
+/// Replaces the hands holding a controller in [`HandsState`] with the controller, sending a
+/// [`PinchEvent`] whenever its trigger starts or stops pinching.
+fn feed_hands_from_controllers(
+    settings: Res<ControllerSettings>,
+    controllers: Res<Controllers>,
+    mut hands: ResMut<HandsState>,
+    mut events: EventWriter<PinchEvent>,
+    mut pinching: Local<[bool; 2]>,
+) {
+    for (i, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let was_pinching = pinching[i];
+        // Separate thresholds, so a trigger resting halfway doesn't flicker the pinch
+        pinching[i] = controllers.0[i].is_some_and(|controller| {
+            if was_pinching {
+                controller.trigger > settings.release_trigger
+            } else {
+                controller.trigger >= settings.pinch_trigger
+            }
+        });
+        match (was_pinching, pinching[i]) {
+            (false, true) => {
+                events.send(PinchEvent::Held(hand));
+            }
+            (true, false) => {
+                events.send(PinchEvent::Released(hand));
+            }
+            _ => {}
+        }
+        if let Some(controller) = controllers.0[i] {
+            *hands.hand_mut(hand) = controller.hand_state(pinching[i], &settings);
+        }
+    }
+}
+
+/// Reads the controllers' trigger, grip and pose from OpenXR into [`Controllers`].
+pub struct OpenXrControllerPlugin;
+
+impl Plugin for OpenXrControllerPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<ControllerPlugin>() {
+            app.add_plugins(ControllerPlugin);
+        }
+        if !app.is_plugin_added::<XRUtilsActionsPlugin>() {
+            app.add_plugins(XRUtilsActionsPlugin);
+        }
+        if !app.is_plugin_added::<TrackingUtilitiesPlugin>() {
+            app.add_plugins(TrackingUtilitiesPlugin);
+        }
+        app.add_systems(Startup, create_controller_actions);
+        app.add_systems(Update, read_controller_actions.before(ControllerSet));
+    }
+}
+
+/// Interaction profiles the actions are bound for, all of them have an analog trigger and grip.
+const PROFILES: [&str; 2] = [
+    "/interaction_profiles/oculus/touch_controller",
+    "/interaction_profiles/valve/index_controller",
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ControllerInput {
+    Trigger,
+    Squeeze,
+}
+
+#[derive(Component, Copy, Clone, Debug)]
+struct ControllerAction {
+    hand: Hand,
+    input: ControllerInput,
+}
+
+fn create_controller_actions(mut commands: Commands) {
+    let set = commands
+        .spawn((
+            XRUtilsActionSet {
+                name: "controllers".into(),
+                pretty_name: "Controllers".into(),
+                priority: u32::MIN,
+            },
+            ActiveSet,
+        ))
+        .id();
+    for hand in [Hand::Left, Hand::Right] {
+        let side = match hand {
+            Hand::Left => "left",
+            Hand::Right => "right",
+        };
+        for input in [ControllerInput::Trigger, ControllerInput::Squeeze] {
+            let name = match input {
+                ControllerInput::Trigger => "trigger",
+                ControllerInput::Squeeze => "squeeze",
+            };
+            let action = commands
+                .spawn((
+                    XRUtilsAction {
+                        action_name: format!("{side}_{name}").into(),
+                        localized_name: format!("{side} {name}").into(),
+                        action_type: ActionType::Float,
+                    },
+                    ControllerAction { hand, input },
+                ))
+                .id();
+            for profile in PROFILES {
+                let binding = commands
+                    .spawn(XRUtilsBinding {
+                        profile: profile.into(),
+                        binding: format!("/user/hand/{side}/input/{name}/value").into(),
+                    })
+                    .id();
+                commands.entity(action).add_child(binding);
+            }
+            commands.entity(set).add_child(action);
+        }
+    }
+    commands.spawn((SpatialBundle::default(), XrTrackedLeftGrip));
+    commands.spawn((SpatialBundle::default(), XrTrackedRightGrip));
+}
+
+fn read_controller_actions(
+    settings: Res<ControllerSettings>,
+    mut controllers: ResMut<Controllers>,
+    actions: Query<(&ControllerAction, &XRUtilsActionState)>,
+    left_grips: Query<Ref<GlobalTransform>, With<XrTrackedLeftGrip>>,
+    right_grips: Query<Ref<GlobalTransform>, With<XrTrackedRightGrip>>,
+) {
+    for (i, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let grip = match hand {
+            Hand::Left => left_grips.get_single(),
+            Hand::Right => right_grips.get_single(),
+        };
+        // The runtime only moves the grip while it has a valid pose, one left where it was is
+        // stale like the bones of a lost hand
+        let Some(grip) = grip.ok().filter(|grip| grip.is_changed()) else {
+            controllers.0[i] = None;
+            continue;
+        };
+        let grip = grip.compute_transform();
+        let mut state = ControllerState {
+            grip,
+            aim: grip * settings.aim_from_grip,
+            trigger: 0.0,
+            squeeze: 0.0,
+        };
+        // The actions are only active while the runtime is using the controller
+        let mut active = false;
+        for (action, action_state) in &actions {
+            let XRUtilsActionState::Float(value) = action_state else {
+                continue;
+            };
+            if action.hand != hand {
+                continue;
+            }
+            active |= value.is_active;
+            match action.input {
+                ControllerInput::Trigger => state.trigger = value.current_state,
+                ControllerInput::Squeeze => state.squeeze = value.current_state,
+            }
+        }
+        controllers.0[i] = active.then_some(state);
+    }
+}
@@ -1,33 +1,107 @@
 use bevy::app::{App, Plugin, Startup, Update};
 use bevy::asset::{AssetServer, Assets};
 use bevy::audio::{AudioBundle, AudioSink, PlaybackMode, PlaybackSettings, Volume};
-use bevy::math::Vec3;
-use bevy::pbr::{PbrBundle, StandardMaterial};
-use bevy::prelude::{default, AudioSinkPlayback, Color, Commands, Cuboid, Deref, DerefMut, Entity, Gizmos, GlobalTransform, Local, Mesh, Query, Res, ResMut, Resource, Transform, With, Event, EventWriter, EventReader};
-use bevy_xpbd_3d::components::{LinearVelocity, RigidBody};
-use bevy_xpbd_3d::prelude::Collider;
-use bevy_xr::hands::{HandBone, LeftHand, RightHand};
-use random_number::random;
+use bevy::ecs::system::SystemParam;
+use bevy::math::{Mat3, Quat, Vec3};
+use bevy::pbr::StandardMaterial;
+use bevy::prelude::{
+    default, AudioSinkPlayback, Color, Commands, Deref, DerefMut, Entity, Event, EventReader,
+    EventWriter, Gizmos, IntoSystemConfigs, Local, Mesh, Query, Res, ResMut, Resource, SystemSet,
+    Transform,
+};
+use bevy_xr::hands::HandBone;
 use std::ops::Deref;
 
+use crate::block::{spawn_block, Block, BlockSpawned};
+use crate::block_material::SelectedMaterial;
+use crate::grab::{GrabSet, Grabbed};
+use crate::hands::{Hand, HandsPlugin, HandsSet, HandsState};
+use crate::palette::{PaletteTextures, SelectedPaint};
+use crate::shapes::SelectedShape;
+use crate::snapping::{BlockPlacement, SnapSettings, SurfaceSnapSettings};
+
 pub struct CubeCreationPlugin;
 
 impl Plugin for CubeCreationPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<HandsPlugin>() {
+            app.add_plugins(HandsPlugin);
+        }
+        app.init_resource::<CubeCreationSettings>();
+        app.init_resource::<SelectedShape>();
+        app.init_resource::<SelectedMaterial>();
+        app.init_resource::<SelectedPaint>();
+        app.init_resource::<PaletteTextures>();
+        app.init_resource::<SnapSettings>();
+        app.init_resource::<SurfaceSnapSettings>();
+        app.init_resource::<CubePreview>();
         app.add_systems(Startup, setup_audio);
-        app.add_systems(Update, (create_cube, draw_cube));
+        app.add_systems(
+            Update,
+            (create_cube, draw_cube)
+                .chain()
+                .in_set(CubeCreationSet)
+                .after(HandsSet)
+                .after(GrabSet),
+        );
         app.add_event::<MakeCube>();
+        app.add_event::<BlockSpawned>();
+    }
+}
+
+/// The systems turning hand gestures into cubes, order systems that feed [`MakeCube`] events
+/// or move the hands before this set.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CubeCreationSet;
+
+/// Tuning values for the cube creation gesture and its audio feedback, the pinch thresholds live
+/// in [`PinchSettings`](crate::pinch::PinchSettings).
+///
+/// Inserted with defaults by [`CubeCreationPlugin`]; insert your own before adding the plugin to
+/// override them.
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct CubeCreationSettings {
+    /// Whether pinching with both hands makes cubes, switch it off to use other tools without
+    /// making cubes by accident.
+    pub enabled: bool,
+    /// Distance in meters between both index tips at which a new cube is started, while both
+    /// hands are pinching.
+    pub start_distance: f32,
+    /// Orient new cubes to the hands instead of aligning them with the world axes.
+    pub align_to_hands: bool,
+    /// Volume of the creation hum while a cube is being made.
+    pub hum_volume: f32,
+    /// Playback speed of the creation hum for a zero sized cube.
+    pub hum_base_speed: f32,
+    /// How much the playback speed of the creation hum increases per meter of cube diagonal.
+    pub hum_speed_per_meter: f32,
+}
+
+impl Default for CubeCreationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            start_distance: 0.03,
+            align_to_hands: true,
+            hum_volume: 0.7,
+            hum_base_speed: 0.1,
+            hum_speed_per_meter: 1.0,
+        }
     }
 }
 
-fn setup_audio(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup_audio(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<CubeCreationSettings>,
+) {
     let creation_hum = commands
         .spawn(
             (AudioBundle {
                 source: asset_server.load("embedded://hum.ogg"),
                 settings: PlaybackSettings {
                     mode: PlaybackMode::Loop,
-                    volume: Volume::new(0.7),
+                    volume: Volume::new(settings.hum_volume),
                     speed: 1.0,
                     paused: false,
                     spatial: false,
@@ -42,157 +116,232 @@ fn setup_audio(mut commands: Commands, asset_server: Res<AssetServer>) {
 #[derive(Resource, Deref, DerefMut, Copy, Clone)]
 pub struct CreationHum(pub Entity);
 
+/// The block being made right now, after snapping, for anything that wants to annotate it.
+#[derive(Resource, Copy, Clone, Debug, Default)]
+pub struct CubePreview(pub Option<Preview>);
+
+#[derive(Copy, Clone, Debug)]
+pub struct Preview {
+    pub block: Block,
+    pub transform: Transform,
+}
+
+/// What the next block is made of and how it looks.
+#[derive(SystemParam)]
+pub struct BlockSelection<'w> {
+    pub shape: Res<'w, SelectedShape>,
+    pub material: Res<'w, SelectedMaterial>,
+    pub paint: Res<'w, SelectedPaint>,
+    pub textures: Res<'w, PaletteTextures>,
+}
+
+impl BlockSelection<'_> {
+    /// The look of a new block, picking a fresh color if the paint is random.
+    pub fn standard_material(&self) -> StandardMaterial {
+        self.textures.standard_material(
+            **self.material,
+            self.paint.paint.color(),
+            self.paint.texture,
+        )
+    }
+}
+
 #[derive(Event, Copy, Clone)]
 pub enum MakeCube {
     StartMaking,
     FinishMaking,
+    /// Drops the cube being made without spawning it.
+    CancelMaking,
 }
 
 fn create_cube(
-    mut audio_query: Query<&mut AudioSink>,
-    audio_thing: Res<CreationHum>,
     mut event_writer: EventWriter<MakeCube>,
-    left_hand: Query<(&HandBone, &GlobalTransform), With<LeftHand>>,
-    right_hand: Query<(&HandBone, &GlobalTransform), With<RightHand>>,
+    settings: Res<CubeCreationSettings>,
+    mut making: Local<bool>,
+    hands: Res<HandsState>,
+    grabbed: Query<&Grabbed>,
 ) {
-    const THUMB_INDEX_DISTANCE: f32 = 0.07;
-    for (left_bone, index_pos) in left_hand.iter() {
-        if !matches!(left_bone, HandBone::IndexTip) {
-            continue;
-        }
-        for (left_bone, thumb_pos) in left_hand.iter() {
-            if !matches!(left_bone, HandBone::ThumbTip) {
-                continue;
-            }
-            if index_pos.translation().distance(thumb_pos.translation()) >= THUMB_INDEX_DISTANCE {
-                event_writer.send(MakeCube::FinishMaking);
-                return;
-            }
-        }
-    }
+    let held = |hand| {
+        let hand = hands.hand(hand);
+        hand.joint(HandBone::IndexTip)
+            .filter(|_| hand.pinching)
+            .map(|index_tip| index_tip.translation)
+    };
+    let (left, right) = (held(Hand::Left), held(Hand::Right));
 
-    for (right_bone, index_pos) in right_hand.iter() {
-        if !matches!(right_bone, HandBone::IndexTip) {
-            continue;
-        }
-        for (right_bone, thumb_pos) in right_hand.iter() {
-            if !matches!(right_bone, HandBone::ThumbTip) {
-                continue;
-            }
-            if index_pos.translation().distance(thumb_pos.translation()) >= THUMB_INDEX_DISTANCE {
-                event_writer.send(MakeCube::FinishMaking);
-                return;
-            }
+    if *making {
+        // Losing track of either hand drops the cube, letting go of either pinch finishes it
+        if !hands.left.tracked || !hands.right.tracked {
+            event_writer.send(MakeCube::CancelMaking);
+            *making = false;
+        } else if left.is_none() || right.is_none() {
+            event_writer.send(MakeCube::FinishMaking);
+            *making = false;
         }
+        return;
+    }
+    if !settings.enabled {
+        return;
     }
 
-    for (left_bone, left_pos) in left_hand.iter() {
-        match left_bone {
-            HandBone::IndexTip => {}
-            _ => continue,
-        }
-        for (right_bone, right_pos) in right_hand.iter() {
-            match right_bone {
-                HandBone::IndexTip => {}
-                _ => continue,
-            }
-
-            if left_pos.translation().distance(right_pos.translation()) <= 0.03 {
-                event_writer.send(MakeCube::StartMaking);
-                audio_query.get(audio_thing.0).unwrap().set_volume(0.7);
-            }
-        }
+    let (Some(left), Some(right)) = (left, right) else {
+        return;
+    };
+    // Pinching onto a block grabs it instead, blocks held by a hand that isn't pinching don't
+    // matter
+    if grabbed.iter().any(|grabbed| held(grabbed.hand).is_some()) {
+        return;
+    }
+    if left.distance(right) <= settings.start_distance {
+        event_writer.send(MakeCube::StartMaking);
+        *making = true;
     }
 }
 
 fn draw_cube(
     mut gizmos: Gizmos,
-    left_hand: Query<(&HandBone, &GlobalTransform), With<LeftHand>>,
-    right_hand: Query<(&HandBone, &GlobalTransform), With<RightHand>>,
+    hands: Res<HandsState>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     audio_thing: Res<CreationHum>,
     mut audio_query: Query<&mut AudioSink>,
+    settings: Res<CubeCreationSettings>,
+    selection: BlockSelection,
+    block_placement: BlockPlacement,
+    mut preview: ResMut<CubePreview>,
     mut make_cube: EventReader<MakeCube>,
+    mut spawned: EventWriter<BlockSpawned>,
     mut current_cube_stage: Local<Option<MakeCube>>,
 ) {
     for e in make_cube.read() {
         match e {
-            MakeCube::StartMaking => {}
+            // The hum is silenced after every cube, whoever starts the next one
+            MakeCube::StartMaking => {
+                if let Ok(sink) = audio_query.get(audio_thing.0) {
+                    sink.set_volume(settings.hum_volume);
+                }
+            }
             MakeCube::FinishMaking => {
                 if current_cube_stage.is_none() {
                     return;
                 }
             }
+            MakeCube::CancelMaking => {
+                current_cube_stage.take();
+                if let Ok(sink) = audio_query.get(audio_thing.0) {
+                    sink.set_volume(0.00);
+                }
+                continue;
+            }
         }
         current_cube_stage.replace(e.clone());
     }
 
-    let Some(cube_stage) = current_cube_stage.as_ref().cloned() else { return };
+    preview.0 = None;
+    let Some(cube_stage) = current_cube_stage.as_ref().cloned() else {
+        return;
+    };
 
-    for (left_bone, left_pos) in left_hand.iter() {
-        if !matches!(left_bone, HandBone::IndexTip) {
-            continue;
+    let (Some(left_tip), Some(right_tip)) = (
+        hands.left.joint(HandBone::IndexTip),
+        hands.right.joint(HandBone::IndexTip),
+    ) else {
+        // Without both tips there is no cube to show or spawn, don't keep it for when they return
+        current_cube_stage.take();
+        if let Ok(sink) = audio_query.get(audio_thing.0) {
+            sink.set_volume(0.00);
         }
-        for (right_bone, right_pos) in right_hand.iter() {
-            if !matches!(right_bone, HandBone::IndexTip) {
-                continue;
-            }
-
-            let mut scale = left_pos.translation() - right_pos.translation();
-
-            scale.y = scale.y.abs();
-            scale.x = scale.x.abs();
-            scale.z = scale.z.abs();
-
-            let mut left_corner = left_pos.translation();
-
-            if right_pos.translation().x < left_corner.x {
-                left_corner.x = right_pos.translation().x;
-            }
+        return;
+    };
+    // The index tips are opposite corners of the cube
+    let diagonal = right_tip.translation - left_tip.translation;
+    let rotation = if settings.align_to_hands {
+        hands_rotation(
+            [
+                hands.left.joint(HandBone::Palm),
+                hands.right.joint(HandBone::Palm),
+            ],
+            diagonal,
+        )
+    } else {
+        Quat::IDENTITY
+    };
+    let scale = (rotation.inverse() * diagonal).abs();
+    let shape = **selection.shape;
+    let placement = block_placement.place(
+        shape,
+        Transform {
+            translation: left_tip.translation + diagonal / 2.0,
+            rotation,
+            scale,
+        },
+        scale,
+    );
+    let (transform, scale) = (placement.transform, placement.size);
+    let block = Block {
+        shape,
+        size: scale,
+        material: **selection.material,
+    };
 
-            if right_pos.translation().y < left_corner.y {
-                left_corner.y = right_pos.translation().y;
-            }
+    if let Ok(sink) = audio_query.get(audio_thing.0) {
+        sink.set_speed(scale.length() * settings.hum_speed_per_meter + settings.hum_base_speed);
+    }
 
-            if right_pos.translation().z < left_corner.z {
-                left_corner.z = right_pos.translation().z;
+    match cube_stage {
+        MakeCube::StartMaking => {
+            shape.draw_gizmo(&mut gizmos, transform, scale, Color::rgb_u8(0, 255, 0));
+            placement.draw_indicator(&mut gizmos, block_placement.surface_snap.indicator_color);
+            preview.0 = Some(Preview { block, transform });
+        }
+        MakeCube::FinishMaking => {
+            let material = materials.add(selection.standard_material());
+            let block = spawn_block(&mut commands, &mut meshes, material, block, transform);
+            spawned.send(BlockSpawned(block));
+            if let Ok(sink) = audio_query.get(audio_thing.0) {
+                sink.set_volume(0.00);
             }
+            current_cube_stage.take();
+        }
+    }
+}
 
-            audio_query
-                .get(audio_thing.0)
-                .unwrap()
-                .set_speed((scale.length() / 1.0) + 0.1);
-
-            let transform =
-                Transform::from_scale(scale).with_translation(left_corner + scale / 2.0);
-
-            match cube_stage {
-                MakeCube::StartMaking => {
-                    gizmos.cuboid(transform, Color::rgb_u8(0, 255, 0));
-                }
-                MakeCube::FinishMaking => {
-                    let mut new_cube = commands.spawn((PbrBundle {
-                        mesh: meshes.add(Cuboid::from_size(scale)),
-                        material: materials.add(Color::rgb_u8(
-                            random!(0..255),
-                            random!(0..255),
-                            random!(0..255),
-                        )),
-                        transform: Transform::from_translation(transform.translation),
-                        ..default()
-                    },));
-                    new_cube.insert((
-                        RigidBody::Dynamic,
-                        LinearVelocity(Vec3::new(0.0, 0.0, 0.0)),
-                        Collider::cuboid(transform.scale.x, transform.scale.y, transform.scale.z),
-                    ));
-                    audio_query.get(audio_thing.0).unwrap().set_volume(0.00);
-                    current_cube_stage.take();
-                }
-            }
+/// Orients a cube to the hands from the average palm normal and the line between the index tips.
+///
+/// With the palms facing each other across that line the hands hold two opposite sides of the
+/// cube, so the palm normal is its X axis and the fingers point along -Z. Otherwise the backs of
+/// the hands are its top and the fingers point along -Z. Without any tracked palm the cube is
+/// aligned with the world axes.
+fn hands_rotation(palms: [Option<Transform>; 2], tips_line: Vec3) -> Quat {
+    let palms: Vec<_> = palms.into_iter().flatten().collect();
+    let Some(first) = palms.first() else {
+        return Quat::IDENTITY;
+    };
+    // OpenXR hand joints have +Y on the back of the hand and -Z pointing along the fingers.
+    // Palms facing each other have opposite normals, so flip them to agree before averaging.
+    let first_normal = *first.up();
+    let normal = palms
+        .iter()
+        .map(|palm| *palm.up() * palm.up().dot(first_normal).signum())
+        .sum::<Vec3>()
+        .try_normalize()
+        .unwrap_or(Vec3::Y);
+    let fingers = palms.iter().map(|palm| *palm.forward()).sum::<Vec3>();
+    let flatten = |v: Vec3, axis: Vec3| (v - axis * v.dot(axis)).try_normalize();
 
-        }
+    let line = tips_line.normalize_or_zero();
+    if normal.dot(line).abs() >= std::f32::consts::FRAC_1_SQRT_2 {
+        let x = normal * normal.dot(line).signum();
+        let forward = flatten(fingers, x)
+            .or_else(|| flatten(Vec3::NEG_Z, x))
+            .unwrap_or_else(|| x.any_orthonormal_vector());
+        let z = -forward;
+        return Quat::from_mat3(&Mat3::from_cols(x, z.cross(x), z));
     }
+    let forward = flatten(fingers, normal)
+        .or_else(|| flatten(Vec3::NEG_Z, normal))
+        .or_else(|| flatten(line, normal))
+        .unwrap_or_else(|| normal.any_orthonormal_vector());
+    Transform::IDENTITY.looking_to(forward, normal).rotation
 }
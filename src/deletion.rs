@@ -0,0 +1,207 @@
+//! Deleting blocks by squeezing them in a fist.
+//!
+//! Closing a hand into a fist, or squeezing a controller's grip, with the palm on a block sends
+//! [`DeleteBlock`] for it. Deleted blocks stop colliding, shrink away and play a sound, and a
+//! [`BlockDeleted`] event with a snapshot of the block lets undo, persistence and anything else
+//! react.
+
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use bevy_xr::hands::HandBone;
+
+use crate::block::{Block, BlockDeleted, BlockId, BlockSnapshot};
+use crate::grab::{GrabSet, Grabbed};
+use crate::hands::{Hand, HandsPlugin, HandsSet, HandsState};
+
+pub struct DeletionPlugin;
+
+impl Plugin for DeletionPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<HandsPlugin>() {
+            app.add_plugins(HandsPlugin);
+        }
+        app.init_resource::<DeletionSettings>();
+        app.add_event::<DeleteBlock>();
+        app.add_event::<BlockDeleted>();
+        app.add_systems(
+            Update,
+            (fist_delete_gesture, delete_blocks, dissolve_blocks)
+                .chain()
+                .in_set(DeletionSet)
+                .after(HandsSet)
+                .after(GrabSet),
+        );
+    }
+}
+
+/// Turns fist gestures into deleted blocks.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DeletionSet;
+
+/// Deletes a block entity.
+#[derive(Event, Copy, Clone, Debug)]
+pub struct DeleteBlock(pub Entity);
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct DeletionSettings {
+    /// Whether making a fist deletes blocks.
+    pub enabled: bool,
+    /// Grab strength at which the hand is a fist.
+    pub fist_strength: f32,
+    /// Grab strength the hand has to open to below before it can delete again.
+    pub open_strength: f32,
+    /// How far in meters from the palm a block gets squeezed.
+    pub reach: f32,
+    /// Seconds a deleted block takes to shrink away.
+    pub dissolve_duration: f32,
+    pub sound_volume: f32,
+    /// Playback speed of the deletion sound, below one for a duller thud than a collision.
+    pub sound_speed: f32,
+}
+
+impl Default for DeletionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            fist_strength: 0.95,
+            open_strength: 0.6,
+            reach: 0.06,
+            dissolve_duration: 0.3,
+            sound_volume: 1.0,
+            sound_speed: 0.6,
+        }
+    }
+}
+
+/// A deleted block shrinking away before it is despawned.
+#[derive(Component, Clone, Debug)]
+pub struct Dissolving {
+    timer: Timer,
+    scale: Vec3,
+}
+
+fn fist_delete_gesture(
+    settings: Res<DeletionSettings>,
+    spatial_query: SpatialQuery,
+    hands: Res<HandsState>,
+    blocks: Query<&GlobalTransform, With<Block>>,
+    mut fists: Local<[bool; 2]>,
+    mut delete: EventWriter<DeleteBlock>,
+) {
+    for (i, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let state = hands.hand(hand);
+        let Some(palm) = state.joint(HandBone::Palm) else {
+            continue;
+        };
+        let palm = palm.translation;
+
+        if fists[i] {
+            fists[i] = state.grab_strength > settings.open_strength;
+            continue;
+        }
+        if state.grab_strength < settings.fist_strength {
+            continue;
+        }
+        fists[i] = true;
+        if !settings.enabled {
+            continue;
+        }
+
+        let closest = spatial_query
+            .shape_intersections(
+                &Collider::sphere(settings.reach),
+                palm,
+                Quat::IDENTITY,
+                SpatialQueryFilter::default(),
+            )
+            .into_iter()
+            .filter_map(|entity| {
+                let transform = blocks.get(entity).ok()?;
+                Some((entity, transform.translation().distance(palm)))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((entity, _)) = closest {
+            delete.send(DeleteBlock(entity));
+        }
+    }
+}
+
+fn delete_blocks(
+    mut commands: Commands,
+    mut events: EventReader<DeleteBlock>,
+    mut deleted: EventWriter<BlockDeleted>,
+    settings: Res<DeletionSettings>,
+    asset_server: Res<AssetServer>,
+    mut blocks: Query<(
+        &BlockId,
+        &Block,
+        &Transform,
+        &Handle<StandardMaterial>,
+        &mut RigidBody,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+    )>,
+) {
+    for DeleteBlock(entity) in events.read() {
+        let Ok((id, block, transform, material, mut body, mut linear, mut angular)) =
+            blocks.get_mut(*entity)
+        else {
+            continue;
+        };
+        deleted.send(BlockDeleted(BlockSnapshot {
+            id: *id,
+            block: *block,
+            transform: *transform,
+            material: material.clone(),
+        }));
+
+        // Keep the block in place without colliding while it dissolves, and make sure nothing
+        // treats it as a block anymore
+        *body = RigidBody::Kinematic;
+        linear.0 = Vec3::ZERO;
+        angular.0 = Vec3::ZERO;
+        commands
+            .entity(*entity)
+            .remove::<(Block, BlockId, Grabbed)>()
+            .insert((
+                Sensor,
+                Dissolving {
+                    timer: Timer::from_seconds(settings.dissolve_duration, TimerMode::Once),
+                    scale: transform.scale,
+                },
+            ));
+
+        commands.spawn((
+            AudioBundle {
+                source: asset_server.load("embedded://plastic-hit.ogg"),
+                settings: PlaybackSettings {
+                    mode: PlaybackMode::Despawn,
+                    volume: Volume::new(settings.sound_volume),
+                    speed: settings.sound_speed,
+                    paused: false,
+                    spatial: true,
+                    spatial_scale: None,
+                },
+            },
+            SpatialBundle::from_transform(Transform::from_translation(transform.translation)),
+        ));
+    }
+}
+
+fn dissolve_blocks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut dissolving: Query<(Entity, &mut Dissolving, &mut Transform)>,
+) {
+    for (entity, mut dissolve, mut transform) in &mut dissolving {
+        dissolve.timer.tick(time.delta());
+        if dissolve.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        // Shrink slowly at first and quickly at the end
+        let t = dissolve.timer.fraction();
+        transform.scale = dissolve.scale * (1.0 - t * t);
+    }
+}
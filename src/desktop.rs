@@ -0,0 +1,375 @@
+//! Flat screen mode for iterating on gameplay without a headset.
+//!
+//! Spawns a fly camera and a pair of mouse driven index tips. Click and drag with the left mouse
+//! button to make a cube between the point you pressed and the cursor, scroll to push the cursor
+//! point further away, the number keys pick the shape and M cycles the material. C cycles the
+//! color around the color wheel and back to random colors, P cycles the texture. G toggles grid
+//! snapping, H cycles the grid size and T toggles snapping onto surfaces. U switches the size
+//! readout between metric and imperial and V toggles its volume and mass. Middle click deletes the
+//! block under the cursor. F5 saves the blocks, F9 loads them, Ctrl+Z and Ctrl+Y undo and redo.
+//! Hold the right mouse button to look around and move with WASD, Q and E.
+
+use bevy::input::mouse::{MouseMotion, MouseWheel};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use bevy_xpbd_3d::prelude::*;
+use bevy_xr::hands::{HandBone, LeftHand, RightHand};
+
+use crate::block_material::{BlockMaterial, SelectedMaterial};
+use crate::cube_creation::{CubeCreationSet, MakeCube};
+use crate::deletion::DeleteBlock;
+use crate::dimensions::{DimensionReadoutSettings, Units};
+use crate::hands::HandsSet;
+use crate::history::HistoryStep;
+use crate::layers::Layer;
+use crate::palette::{hsv, BlockTexture, Paint, SelectedPaint};
+use crate::persistence::{LoadBlocks, SaveBlocks};
+use crate::shapes::{SelectedShape, ShapeKind};
+use crate::snapping::{SnapSettings, SurfaceSnapSettings};
+
+/// Where the mouse index tips wait while no cube is being made, far enough apart that
+/// `create_cube` doesn't start a cube on its own.
+const PARKED_LEFT: Vec3 = Vec3::new(0.0, -100.0, 0.0);
+const PARKED_RIGHT: Vec3 = Vec3::new(0.0, -101.0, 0.0);
+
+pub struct DesktopPlugin;
+
+impl Plugin for DesktopPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ClearColor(Color::rgb(0.55, 0.7, 0.85)));
+        app.init_resource::<MouseCubeDrag>();
+        app.add_systems(Startup, (spawn_fly_camera, spawn_mouse_hands));
+        app.add_systems(
+            Update,
+            (
+                fly_camera,
+                select_shape,
+                select_material,
+                select_paint,
+                toggle_snapping,
+                toggle_readout,
+                keyboard_shortcuts,
+                mouse_cube_creation,
+                mouse_delete,
+            )
+                .before(CubeCreationSet),
+        );
+        // The tips have to be in place before the hands are read, not a frame later
+        app.add_systems(
+            Update,
+            write_mouse_hand_globals
+                .after(mouse_cube_creation)
+                .before(HandsSet),
+        );
+    }
+}
+
+#[derive(Component, Copy, Clone)]
+pub struct FlyCamera {
+    /// Movement speed in meters per second.
+    pub speed: f32,
+    /// Rotation in radians per pixel of mouse movement.
+    pub sensitivity: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Default for FlyCamera {
+    fn default() -> Self {
+        Self {
+            speed: 1.5,
+            sensitivity: 0.003,
+            yaw: 0.0,
+            pitch: -0.4,
+        }
+    }
+}
+
+/// Marks the index tip entities driven by the mouse.
+#[derive(Component, Copy, Clone)]
+pub struct MouseHand;
+
+#[derive(Resource, Copy, Clone, Default)]
+struct MouseCubeDrag {
+    /// Distance along the cursor ray at which the cube corner is placed, `None` while not dragging.
+    depth: Option<f32>,
+}
+
+fn spawn_fly_camera(mut commands: Commands) {
+    let fly_camera = FlyCamera::default();
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 1.6, 1.0).with_rotation(Quat::from_euler(
+                EulerRot::YXZ,
+                fly_camera.yaw,
+                fly_camera.pitch,
+                0.0,
+            )),
+            ..default()
+        },
+        fly_camera,
+    ));
+}
+
+fn spawn_mouse_hands(mut commands: Commands) {
+    commands.spawn((
+        HandBone::IndexTip,
+        LeftHand,
+        MouseHand,
+        SpatialBundle::from_transform(Transform::from_translation(PARKED_LEFT)),
+    ));
+    commands.spawn((
+        HandBone::IndexTip,
+        RightHand,
+        MouseHand,
+        SpatialBundle::from_transform(Transform::from_translation(PARKED_RIGHT)),
+    ));
+}
+
+fn fly_camera(
+    time: Res<Time>,
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut motion: EventReader<MouseMotion>,
+    mut cameras: Query<(&mut Transform, &mut FlyCamera)>,
+) {
+    let mouse_delta: Vec2 = motion.read().map(|e| e.delta).sum();
+    for (mut transform, mut fly_camera) in &mut cameras {
+        if buttons.pressed(MouseButton::Right) {
+            fly_camera.yaw -= mouse_delta.x * fly_camera.sensitivity;
+            fly_camera.pitch =
+                (fly_camera.pitch - mouse_delta.y * fly_camera.sensitivity).clamp(-1.5, 1.5);
+            transform.rotation =
+                Quat::from_euler(EulerRot::YXZ, fly_camera.yaw, fly_camera.pitch, 0.0);
+        }
+
+        let mut direction = Vec3::ZERO;
+        if keys.pressed(KeyCode::KeyW) {
+            direction += *transform.forward();
+        }
+        if keys.pressed(KeyCode::KeyS) {
+            direction -= *transform.forward();
+        }
+        if keys.pressed(KeyCode::KeyD) {
+            direction += *transform.right();
+        }
+        if keys.pressed(KeyCode::KeyA) {
+            direction -= *transform.right();
+        }
+        if keys.pressed(KeyCode::KeyE) {
+            direction += Vec3::Y;
+        }
+        if keys.pressed(KeyCode::KeyQ) {
+            direction -= Vec3::Y;
+        }
+        transform.translation +=
+            direction.normalize_or_zero() * fly_camera.speed * time.delta_seconds();
+    }
+}
+
+fn select_shape(keys: Res<ButtonInput<KeyCode>>, mut shape: ResMut<SelectedShape>) {
+    let number_keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+    ];
+    for (key, kind) in number_keys.into_iter().zip(ShapeKind::ALL) {
+        if keys.just_pressed(key) {
+            **shape = kind;
+        }
+    }
+}
+
+fn select_material(keys: Res<ButtonInput<KeyCode>>, mut material: ResMut<SelectedMaterial>) {
+    if keys.just_pressed(KeyCode::KeyM) {
+        let index = BlockMaterial::ALL.iter().position(|m| *m == **material);
+        **material = BlockMaterial::ALL[index.map_or(0, |i| (i + 1) % BlockMaterial::ALL.len())];
+        info!("making {:?} blocks", **material);
+    }
+}
+
+fn select_paint(keys: Res<ButtonInput<KeyCode>>, mut selected: ResMut<SelectedPaint>) {
+    if keys.just_pressed(KeyCode::KeyC) {
+        // Random, then twelve hues around the color wheel
+        let hues: Vec<_> = (0..12)
+            .map(|step| hsv(step as f32 * 30.0, 1.0, 1.0))
+            .collect();
+        let index = match selected.paint {
+            Paint::Color(color) => hues.iter().position(|hue| *hue == color),
+            Paint::Random => None,
+        };
+        selected.paint = match index.map_or(0, |i| i + 1) {
+            i if i < hues.len() => Paint::Color(hues[i]),
+            _ => Paint::Random,
+        };
+        info!("painting with {:?}", selected.paint);
+    }
+    if keys.just_pressed(KeyCode::KeyP) {
+        let index = BlockTexture::ALL
+            .iter()
+            .position(|t| *t == selected.texture);
+        selected.texture =
+            BlockTexture::ALL[index.map_or(0, |i| (i + 1) % BlockTexture::ALL.len())];
+        info!("painting {:?} blocks", selected.texture);
+    }
+}
+
+fn toggle_snapping(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut snap: ResMut<SnapSettings>,
+    mut surface_snap: ResMut<SurfaceSnapSettings>,
+) {
+    if keys.just_pressed(KeyCode::KeyG) {
+        snap.enabled = !snap.enabled;
+        info!("grid snapping {}", if snap.enabled { "on" } else { "off" });
+    }
+    if keys.just_pressed(KeyCode::KeyH) {
+        snap.cycle_grid_size();
+        info!("grid size {} cm", snap.grid_size * 100.0);
+    }
+    if keys.just_pressed(KeyCode::KeyT) {
+        surface_snap.enabled = !surface_snap.enabled;
+        info!(
+            "surface snapping {}",
+            if surface_snap.enabled { "on" } else { "off" }
+        );
+    }
+}
+
+fn toggle_readout(keys: Res<ButtonInput<KeyCode>>, mut readout: ResMut<DimensionReadoutSettings>) {
+    if keys.just_pressed(KeyCode::KeyU) {
+        readout.units = match readout.units {
+            Units::Metric => Units::Imperial,
+            Units::Imperial => Units::Metric,
+        };
+    }
+    if keys.just_pressed(KeyCode::KeyV) {
+        readout.show_volume = !readout.show_volume;
+        readout.show_mass = readout.show_volume;
+    }
+}
+
+fn keyboard_shortcuts(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut save: EventWriter<SaveBlocks>,
+    mut load: EventWriter<LoadBlocks>,
+    mut history: EventWriter<HistoryStep>,
+) {
+    if keys.just_pressed(KeyCode::F5) {
+        save.send(SaveBlocks);
+    }
+    if keys.just_pressed(KeyCode::F9) {
+        load.send(LoadBlocks);
+    }
+    if keys.pressed(KeyCode::ControlLeft) && keys.just_pressed(KeyCode::KeyZ) {
+        history.send(HistoryStep::Undo);
+    }
+    if keys.pressed(KeyCode::ControlLeft) && keys.just_pressed(KeyCode::KeyY) {
+        history.send(HistoryStep::Redo);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn mouse_cube_creation(
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut wheel: EventReader<MouseWheel>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<FlyCamera>>,
+    mut left_hand: Query<&mut Transform, (With<MouseHand>, With<LeftHand>)>,
+    mut right_hand: Query<&mut Transform, (With<MouseHand>, With<RightHand>, Without<LeftHand>)>,
+    mut drag: ResMut<MouseCubeDrag>,
+    mut make_cube: EventWriter<MakeCube>,
+) {
+    let scroll: f32 = wheel.read().map(|e| e.y).sum();
+    let (Ok(mut left), Ok(mut right)) = (left_hand.get_single_mut(), right_hand.get_single_mut())
+    else {
+        return;
+    };
+
+    // Keep the tips in place on the frame the cube is finished, park them on the next one.
+    if drag.depth.is_none() {
+        left.translation = PARKED_LEFT;
+        right.translation = PARKED_RIGHT;
+    }
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+    let Some(ray) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+    else {
+        return;
+    };
+
+    if buttons.just_pressed(MouseButton::Left) {
+        // Start on the floor if the cursor points at it, otherwise at arms length.
+        let depth = ray
+            .intersect_plane(Vec3::Y, Plane3d::new(Vec3::Y))
+            .filter(|depth| *depth < 5.0)
+            .unwrap_or(0.6);
+        drag.depth = Some(depth);
+        left.translation = ray.get_point(depth);
+        right.translation = left.translation;
+        make_cube.send(MakeCube::StartMaking);
+        return;
+    }
+
+    let Some(depth) = drag.depth.as_mut() else {
+        return;
+    };
+    *depth = (*depth + scroll * 0.05).max(0.05);
+    right.translation = ray.get_point(*depth);
+
+    if buttons.just_released(MouseButton::Left) {
+        drag.depth = None;
+        make_cube.send(MakeCube::FinishMaking);
+    }
+}
+
+/// Copies the mouse tips' transforms to their global transforms right away instead of waiting
+/// for transform propagation in `PostUpdate`, every frame so the mouse hands count as tracked.
+fn write_mouse_hand_globals(mut hands: Query<(&Transform, &mut GlobalTransform), With<MouseHand>>) {
+    for (transform, mut global_transform) in &mut hands {
+        *global_transform = GlobalTransform::from(*transform);
+    }
+}
+
+fn mouse_delete(
+    buttons: Res<ButtonInput<MouseButton>>,
+    spatial_query: SpatialQuery,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<FlyCamera>>,
+    mut delete: EventWriter<DeleteBlock>,
+) {
+    if !buttons.just_pressed(MouseButton::Middle) {
+        return;
+    }
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = cameras.get_single() else {
+        return;
+    };
+    let Some(ray) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+    else {
+        return;
+    };
+    // Anything that isn't a block is ignored by the deletion, UI panels don't hide what's behind
+    if let Some(hit) = spatial_query.cast_ray(
+        ray.origin,
+        ray.direction,
+        100.0,
+        true,
+        SpatialQueryFilter::from_mask([Layer::Hands, Layer::Blocks, Layer::Floor, Layer::Held]),
+    ) {
+        delete.send(DeleteBlock(hit.entity));
+    }
+}
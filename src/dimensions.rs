@@ -0,0 +1,138 @@
+//! A floating readout of the size of the block being made.
+//!
+//! While a block is being stretched its width, height and depth, and optionally its volume and
+//! estimated mass, float above the preview facing the viewer, so blocks can be built to measure.
+
+use bevy::prelude::*;
+
+use crate::block::Block;
+use crate::cube_creation::{CubeCreationSet, CubePreview};
+use crate::gizmo_text::{draw_text, TextAnchor};
+
+pub struct DimensionReadoutPlugin;
+
+impl Plugin for DimensionReadoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DimensionReadoutSettings>();
+        app.add_systems(Update, draw_dimension_readout.after(CubeCreationSet));
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct DimensionReadoutSettings {
+    pub enabled: bool,
+    pub units: Units,
+    pub show_volume: bool,
+    /// Show the mass the block will have with its material.
+    pub show_mass: bool,
+    /// Height of the letters in meters.
+    pub text_height: f32,
+    pub color: Color,
+}
+
+impl Default for DimensionReadoutSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            units: Units::Metric,
+            show_volume: false,
+            show_mass: false,
+            text_height: 0.01,
+            color: Color::WHITE,
+        }
+    }
+}
+
+const INCHES_PER_METER: f32 = 39.370_08;
+const POUNDS_PER_KILOGRAM: f32 = 2.204_623;
+
+impl DimensionReadoutSettings {
+    /// The readout text for `block`, one measurement per line.
+    pub fn format(&self, block: &Block) -> String {
+        let Vec3 { x, y, z } = block.size;
+        let volume = block.shape.volume(block.size);
+        let mass = volume * block.material.density().0;
+        let mut lines = Vec::new();
+        match self.units {
+            Units::Metric => {
+                let [x, y, z] = [x, y, z].map(|meters| meters * 100.0);
+                lines.push(format!("{x:.1} × {y:.1} × {z:.1} cm"));
+                if self.show_volume {
+                    let liters = volume * 1000.0;
+                    lines.push(if liters < 1.0 {
+                        format!("{:.0} cm³", liters * 1000.0)
+                    } else {
+                        format!("{liters:.2} l")
+                    });
+                }
+                if self.show_mass {
+                    lines.push(if mass < 1.0 {
+                        format!("{:.0} g", mass * 1000.0)
+                    } else {
+                        format!("{mass:.2} kg")
+                    });
+                }
+            }
+            Units::Imperial => {
+                let [x, y, z] = [x, y, z].map(|meters| meters * INCHES_PER_METER);
+                lines.push(format!("{x:.2} × {y:.2} × {z:.2} in"));
+                if self.show_volume {
+                    lines.push(format!("{:.1} in³", volume * INCHES_PER_METER.powi(3)));
+                }
+                if self.show_mass {
+                    let pounds = mass * POUNDS_PER_KILOGRAM;
+                    lines.push(if pounds < 1.0 {
+                        format!("{:.1} oz", pounds * 16.0)
+                    } else {
+                        format!("{pounds:.2} lb")
+                    });
+                }
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+fn draw_dimension_readout(
+    mut gizmos: Gizmos,
+    settings: Res<DimensionReadoutSettings>,
+    preview: Res<CubePreview>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+) {
+    let Some(preview) = preview.0.filter(|_| settings.enabled) else {
+        return;
+    };
+    let Some(eye) = cameras.iter().next().map(|camera| camera.translation()) else {
+        return;
+    };
+
+    // Float above the top of the box around the preview
+    let rotation = Mat3::from_quat(preview.transform.rotation);
+    let half_height = (rotation.row(1).abs() * preview.block.size / 2.0).element_sum();
+    let text = settings.format(&preview.block);
+    let lines = text.lines().count() as f32;
+    let position = preview.transform.translation
+        + Vec3::Y * (half_height + settings.text_height * (lines + 1.0));
+
+    // Face the viewer, upright
+    let away = position - eye;
+    let away = Vec3::new(away.x, 0.0, away.z)
+        .try_normalize()
+        .unwrap_or(Vec3::NEG_Z);
+    let transform = Transform::from_translation(position).looking_to(away, Vec3::Y);
+    draw_text(
+        &mut gizmos,
+        &text,
+        transform,
+        settings.text_height,
+        TextAnchor::Center,
+        settings.color,
+    );
+}
@@ -0,0 +1,249 @@
+//! Text drawn in the world with gizmo lines.
+//!
+//! A small stroke font covering digits, letters (shown upper case) and the symbols needed for
+//! measurements and labels. Gizmos render in every view, including the headset's, without fonts,
+//! meshes or a UI camera, which makes them handy for short floating labels.
+
+use bevy::prelude::*;
+
+/// Width of a glyph relative to its height.
+const GLYPH_WIDTH: f32 = 0.6;
+/// Distance from one glyph to the next relative to the height.
+const ADVANCE: f32 = 0.85;
+/// Distance from one line to the next relative to the height.
+const LINE_SPACING: f32 = 1.6;
+
+/// Which part of the text sits at the position it is drawn at, vertically it is always the middle
+/// of the block of lines.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TextAnchor {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+type Stroke = &'static [(f32, f32)];
+
+/// The strokes of `c` in a unit box with the origin at the bottom left.
+fn glyph(c: char) -> &'static [Stroke] {
+    match c.to_ascii_uppercase() {
+        '0' => &[
+            &[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)],
+            &[(0.0, 0.0), (1.0, 1.0)],
+        ],
+        '1' => &[
+            &[(0.25, 0.75), (0.5, 1.0), (0.5, 0.0)],
+            &[(0.25, 0.0), (0.75, 0.0)],
+        ],
+        '2' => &[&[
+            (0.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 0.5),
+            (0.0, 0.5),
+            (0.0, 0.0),
+            (1.0, 0.0),
+        ]],
+        '3' => &[
+            &[(0.0, 1.0), (1.0, 1.0), (1.0, 0.0), (0.0, 0.0)],
+            &[(0.2, 0.5), (1.0, 0.5)],
+        ],
+        '4' => &[
+            &[(0.0, 1.0), (0.0, 0.5), (1.0, 0.5)],
+            &[(1.0, 1.0), (1.0, 0.0)],
+        ],
+        '5' | 'S' => &[&[
+            (1.0, 1.0),
+            (0.0, 1.0),
+            (0.0, 0.5),
+            (1.0, 0.5),
+            (1.0, 0.0),
+            (0.0, 0.0),
+        ]],
+        '6' => &[&[
+            (1.0, 1.0),
+            (0.0, 1.0),
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (1.0, 0.5),
+            (0.0, 0.5),
+        ]],
+        '7' => &[&[(0.0, 1.0), (1.0, 1.0), (0.4, 0.0)]],
+        '8' => &[
+            &[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)],
+            &[(0.0, 0.5), (1.0, 0.5)],
+        ],
+        '9' => &[&[
+            (1.0, 0.5),
+            (0.0, 0.5),
+            (0.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 0.0),
+            (0.0, 0.0),
+        ]],
+        'A' => &[
+            &[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)],
+            &[(0.0, 0.5), (1.0, 0.5)],
+        ],
+        'B' => &[
+            &[(0.0, 0.0), (0.0, 1.0), (0.75, 1.0), (0.75, 0.5), (0.0, 0.5)],
+            &[(0.75, 0.5), (1.0, 0.5), (1.0, 0.0), (0.0, 0.0)],
+        ],
+        'C' => &[&[(1.0, 1.0), (0.0, 1.0), (0.0, 0.0), (1.0, 0.0)]],
+        'D' => &[&[
+            (0.0, 0.0),
+            (0.0, 1.0),
+            (0.6, 1.0),
+            (1.0, 0.6),
+            (1.0, 0.4),
+            (0.6, 0.0),
+            (0.0, 0.0),
+        ]],
+        'E' => &[
+            &[(1.0, 1.0), (0.0, 1.0), (0.0, 0.0), (1.0, 0.0)],
+            &[(0.0, 0.5), (0.7, 0.5)],
+        ],
+        'F' => &[
+            &[(1.0, 1.0), (0.0, 1.0), (0.0, 0.0)],
+            &[(0.0, 0.5), (0.7, 0.5)],
+        ],
+        'G' => &[&[
+            (1.0, 1.0),
+            (0.0, 1.0),
+            (0.0, 0.0),
+            (1.0, 0.0),
+            (1.0, 0.5),
+            (0.5, 0.5),
+        ]],
+        'H' => &[
+            &[(0.0, 0.0), (0.0, 1.0)],
+            &[(1.0, 0.0), (1.0, 1.0)],
+            &[(0.0, 0.5), (1.0, 0.5)],
+        ],
+        'I' => &[
+            &[(0.0, 1.0), (1.0, 1.0)],
+            &[(0.5, 1.0), (0.5, 0.0)],
+            &[(0.0, 0.0), (1.0, 0.0)],
+        ],
+        'J' => &[&[(1.0, 1.0), (1.0, 0.0), (0.0, 0.0), (0.0, 0.3)]],
+        'K' => &[
+            &[(0.0, 0.0), (0.0, 1.0)],
+            &[(1.0, 1.0), (0.0, 0.5), (1.0, 0.0)],
+        ],
+        'L' => &[&[(0.0, 1.0), (0.0, 0.0), (1.0, 0.0)]],
+        'M' => &[&[(0.0, 0.0), (0.0, 1.0), (0.5, 0.5), (1.0, 1.0), (1.0, 0.0)]],
+        'N' => &[&[(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)]],
+        'O' => &[&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)]],
+        'P' => &[&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.5), (0.0, 0.5)]],
+        'Q' => &[
+            &[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)],
+            &[(0.6, 0.4), (1.0, 0.0)],
+        ],
+        'R' => &[&[
+            (0.0, 0.0),
+            (0.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 0.5),
+            (0.0, 0.5),
+            (1.0, 0.0),
+        ]],
+        'T' => &[&[(0.0, 1.0), (1.0, 1.0)], &[(0.5, 1.0), (0.5, 0.0)]],
+        'U' => &[&[(0.0, 1.0), (0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]],
+        'V' => &[&[(0.0, 1.0), (0.5, 0.0), (1.0, 1.0)]],
+        'W' => &[&[(0.0, 1.0), (0.25, 0.0), (0.5, 0.5), (0.75, 0.0), (1.0, 1.0)]],
+        'X' => &[&[(0.0, 0.0), (1.0, 1.0)], &[(0.0, 1.0), (1.0, 0.0)]],
+        'Y' => &[
+            &[(0.0, 1.0), (0.5, 0.5), (1.0, 1.0)],
+            &[(0.5, 0.5), (0.5, 0.0)],
+        ],
+        'Z' => &[&[(0.0, 1.0), (1.0, 1.0), (0.0, 0.0), (1.0, 0.0)]],
+        '.' => &[&[(0.4, 0.0), (0.6, 0.0), (0.6, 0.1), (0.4, 0.1), (0.4, 0.0)]],
+        ',' => &[&[(0.5, 0.1), (0.3, -0.15)]],
+        '-' => &[&[(0.2, 0.5), (0.8, 0.5)]],
+        '+' => &[&[(0.2, 0.5), (0.8, 0.5)], &[(0.5, 0.2), (0.5, 0.8)]],
+        '=' => &[&[(0.2, 0.35), (0.8, 0.35)], &[(0.2, 0.65), (0.8, 0.65)]],
+        '/' => &[&[(0.0, 0.0), (1.0, 1.0)]],
+        ':' => &[&[(0.5, 0.2), (0.5, 0.3)], &[(0.5, 0.7), (0.5, 0.8)]],
+        '\'' => &[&[(0.5, 1.0), (0.5, 0.7)]],
+        '"' => &[&[(0.3, 1.0), (0.3, 0.7)], &[(0.7, 1.0), (0.7, 0.7)]],
+        '(' => &[&[(0.7, 1.0), (0.3, 0.7), (0.3, 0.3), (0.7, 0.0)]],
+        ')' => &[&[(0.3, 1.0), (0.7, 0.7), (0.7, 0.3), (0.3, 0.0)]],
+        '<' => &[&[(0.8, 1.0), (0.2, 0.5), (0.8, 0.0)]],
+        '>' => &[&[(0.2, 1.0), (0.8, 0.5), (0.2, 0.0)]],
+        '%' => &[
+            &[(0.0, 0.0), (1.0, 1.0)],
+            &[(0.0, 1.0), (0.2, 1.0), (0.2, 0.8), (0.0, 0.8), (0.0, 1.0)],
+            &[(0.8, 0.2), (1.0, 0.2), (1.0, 0.0), (0.8, 0.0), (0.8, 0.2)],
+        ],
+        '×' => &[&[(0.2, 0.2), (0.8, 0.8)], &[(0.2, 0.8), (0.8, 0.2)]],
+        '²' => &[&[
+            (0.3, 1.0),
+            (0.7, 1.0),
+            (0.7, 0.85),
+            (0.3, 0.85),
+            (0.3, 0.7),
+            (0.7, 0.7),
+        ]],
+        '³' => &[
+            &[(0.3, 1.0), (0.7, 1.0), (0.7, 0.7), (0.3, 0.7)],
+            &[(0.4, 0.85), (0.7, 0.85)],
+        ],
+        ' ' => &[],
+        _ => &[
+            &[(0.0, 1.0), (1.0, 1.0), (1.0, 0.5), (0.5, 0.5), (0.5, 0.25)],
+            &[(0.5, 0.1), (0.5, 0.0)],
+        ],
+    }
+}
+
+/// Width of the longest line of `text` drawn `height` tall.
+pub fn text_width(text: &str, height: f32) -> f32 {
+    text.lines()
+        .map(|line| line.chars().count())
+        .max()
+        .map_or(0.0, |chars| line_width(chars, height))
+}
+
+fn line_width(chars: usize, height: f32) -> f32 {
+    if chars == 0 {
+        return 0.0;
+    }
+    ((chars - 1) as f32 * ADVANCE + GLYPH_WIDTH) * height
+}
+
+/// Draws `text` with capital letters `height` tall on the XY plane of `transform`, reading along
+/// its X axis, ignoring its scale.
+pub fn draw_text(
+    gizmos: &mut Gizmos,
+    text: &str,
+    transform: Transform,
+    height: f32,
+    anchor: TextAnchor,
+    color: Color,
+) {
+    let lines = text.lines().count();
+    let right = transform.rotation * Vec3::X;
+    let up = transform.rotation * Vec3::Y;
+    // Center the block of lines vertically
+    let top = (lines as f32 - 1.0) * LINE_SPACING * height / 2.0 + height / 2.0;
+    for (row, line) in text.lines().enumerate() {
+        let width = line_width(line.chars().count(), height);
+        let start = match anchor {
+            TextAnchor::Left => 0.0,
+            TextAnchor::Center => -width / 2.0,
+            TextAnchor::Right => -width,
+        };
+        let bottom = top - height - row as f32 * LINE_SPACING * height;
+        for (column, c) in line.chars().enumerate() {
+            let left = start + column as f32 * ADVANCE * height;
+            let point = |(x, y): (f32, f32)| {
+                transform.translation
+                    + right * (left + x * GLYPH_WIDTH * height)
+                    + up * (bottom + y * height)
+            };
+            for stroke in glyph(c) {
+                gizmos.linestrip(stroke.iter().copied().map(point), color);
+            }
+        }
+    }
+}
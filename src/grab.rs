@@ -0,0 +1,218 @@
+//! Grabbing, carrying and throwing blocks with a pinch.
+//!
+//! Pinching next to a block attaches it to the pinch point, the block turns kinematic and is
+//! driven by velocity towards where the hand carries it so it still pushes other blocks around.
+//! Letting go makes it dynamic again with the hand's recent velocity, so blocks can be thrown.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+
+use crate::block::{Block, BlockId, BlockMoved};
+use crate::hands::{Hand, HandsPlugin, HandsSet, HandsState};
+use crate::layers::Layer;
+use crate::pinch::PinchEvent;
+
+pub struct GrabPlugin;
+
+impl Plugin for GrabPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<HandsPlugin>() {
+            app.add_plugins(HandsPlugin);
+        }
+        app.init_resource::<GrabSettings>();
+        app.add_event::<BlockMoved>();
+        app.add_systems(
+            Update,
+            (grab_blocks, carry_blocks, release_blocks)
+                .chain()
+                .in_set(GrabSet)
+                .after(HandsSet),
+        );
+    }
+}
+
+/// Grabs, carries and releases blocks.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GrabSet;
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct GrabSettings {
+    /// How far in meters from the pinch point a block can be grabbed.
+    pub grab_radius: f32,
+    /// Seconds of hand movement averaged into the velocity a block is released with.
+    pub velocity_window: f32,
+    /// Multiplier on the release velocity, above one makes throws feel stronger.
+    pub throw_multiplier: f32,
+}
+
+impl Default for GrabSettings {
+    fn default() -> Self {
+        Self {
+            grab_radius: 0.03,
+            velocity_window: 0.1,
+            throw_multiplier: 1.0,
+        }
+    }
+}
+
+/// A block held by a hand.
+#[derive(Component, Clone, Debug)]
+pub struct Grabbed {
+    pub hand: Hand,
+    /// The block's transform relative to the pinch point when it was grabbed.
+    offset: Transform,
+    /// Where the block was before it was picked up, kept when handing it to the other hand.
+    start: Transform,
+    /// Recent pinch point positions with the time they were sampled at.
+    history: VecDeque<(f32, Vec3)>,
+}
+
+impl Grabbed {
+    /// Average velocity of the hand over the sampled history.
+    fn velocity(&self) -> Vec3 {
+        match (self.history.front(), self.history.back()) {
+            (Some((start_time, start)), Some((end_time, end))) if end_time > start_time => {
+                (*end - *start) / (end_time - start_time)
+            }
+            _ => Vec3::ZERO,
+        }
+    }
+}
+
+fn grab_blocks(
+    mut commands: Commands,
+    mut pinch_events: EventReader<PinchEvent>,
+    hands: Res<HandsState>,
+    settings: Res<GrabSettings>,
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    mut blocks: Query<(Entity, &GlobalTransform, &mut RigidBody, Option<&Grabbed>), With<Block>>,
+) {
+    for event in pinch_events.read() {
+        let PinchEvent::Held(hand) = *event else {
+            continue;
+        };
+        let Some(frame) = hands.hand(hand).pinch_point else {
+            continue;
+        };
+
+        let closest = spatial_query
+            .shape_intersections(
+                &Collider::sphere(settings.grab_radius),
+                frame.translation,
+                Quat::IDENTITY,
+                SpatialQueryFilter::default(),
+            )
+            .into_iter()
+            .filter_map(|entity| blocks.get(entity).ok())
+            .map(|(entity, transform, _, _)| {
+                (entity, transform.translation().distance(frame.translation))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        let Some((entity, _)) = closest else { continue };
+        let Ok((_, transform, mut body, grabbed)) = blocks.get_mut(entity) else {
+            continue;
+        };
+
+        // Grabbing a block held by the other hand hands it over
+        let start = grabbed.map_or(transform.compute_transform(), |grabbed| grabbed.start);
+        *body = RigidBody::Kinematic;
+        commands.entity(entity).insert((
+            Layer::Held.collision_layers(),
+            Grabbed {
+                hand,
+                offset: transform.reparented_to(&GlobalTransform::from(frame)),
+                start,
+                history: VecDeque::from([(time.elapsed_seconds(), frame.translation)]),
+            },
+        ));
+    }
+}
+
+fn carry_blocks(
+    time: Res<Time>,
+    settings: Res<GrabSettings>,
+    hands: Res<HandsState>,
+    mut blocks: Query<(
+        &mut Grabbed,
+        &Position,
+        &Rotation,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+    )>,
+) {
+    let delta = time.delta_seconds();
+    if delta <= 0.0 {
+        return;
+    }
+    let now = time.elapsed_seconds();
+    for (mut grabbed, position, rotation, mut linear, mut angular) in &mut blocks {
+        // Hold still while the hand isn't tracked instead of flying off with the last velocity
+        let Some(frame) = hands.hand(grabbed.hand).pinch_point else {
+            linear.0 = Vec3::ZERO;
+            angular.0 = Vec3::ZERO;
+            continue;
+        };
+
+        grabbed.history.push_back((now, frame.translation));
+        while grabbed
+            .history
+            .front()
+            .is_some_and(|(time, _)| now - time > settings.velocity_window)
+        {
+            grabbed.history.pop_front();
+        }
+
+        // Drive the kinematic body to the target instead of teleporting it, so it carries
+        // momentum into whatever it hits on the way
+        let target = frame * grabbed.offset;
+        linear.0 = (target.translation - position.0) / delta;
+        let (axis, angle) = (target.rotation * rotation.0.inverse()).to_axis_angle();
+        // Take the short way around
+        let angle = if angle > std::f32::consts::PI {
+            angle - std::f32::consts::TAU
+        } else {
+            angle
+        };
+        angular.0 = axis * angle / delta;
+    }
+}
+
+fn release_blocks(
+    mut commands: Commands,
+    mut pinch_events: EventReader<PinchEvent>,
+    mut moved: EventWriter<BlockMoved>,
+    settings: Res<GrabSettings>,
+    mut blocks: Query<(
+        Entity,
+        &Grabbed,
+        &BlockId,
+        &Transform,
+        &mut RigidBody,
+        &mut LinearVelocity,
+    )>,
+) {
+    for event in pinch_events.read() {
+        let PinchEvent::Released(hand) = *event else {
+            continue;
+        };
+        for (entity, grabbed, id, transform, mut body, mut linear) in &mut blocks {
+            if grabbed.hand != hand {
+                continue;
+            }
+            *body = RigidBody::Dynamic;
+            linear.0 = grabbed.velocity() * settings.throw_multiplier;
+            commands
+                .entity(entity)
+                .remove::<Grabbed>()
+                .insert(Layer::Blocks.collision_layers());
+            moved.send(BlockMoved {
+                id: *id,
+                from: grabbed.start,
+                to: *transform,
+            });
+        }
+    }
+}
@@ -0,0 +1,397 @@
+//! A menu on the left wrist, opened by turning the palm up and looking at it.
+//!
+//! The menu floats just above the palm facing the viewer. Its [widgets](crate::widgets) switch the
+//! building, painting and deleting tools on and off, pick the shape or material of new blocks,
+//! toggle the building aids, set the strength of gravity, undo and redo, or save and load the
+//! scene.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use bevy_xr::hands::HandBone;
+
+use crate::block_material::{BlockMaterial, SelectedMaterial};
+use crate::cube_creation::CubeCreationSettings;
+use crate::deletion::DeletionSettings;
+use crate::dimensions::DimensionReadoutSettings;
+use crate::hands::{HandsSet, HandsState};
+use crate::history::HistoryStep;
+use crate::palette::PaletteSettings;
+use crate::persistence::{LoadBlocks, SaveBlocks};
+use crate::shapes::{SelectedShape, ShapeKind};
+use crate::snapping::{SnapSettings, SurfaceSnapSettings};
+use crate::widgets::{
+    ButtonClicked, Panel, PokeButton, Slider, SliderChanged, Toggle, ToggleChanged, Widget,
+    WidgetPlugin, WidgetSet,
+};
+
+pub struct HandMenuPlugin;
+
+impl Plugin for HandMenuPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<WidgetPlugin>() {
+            app.add_plugins(WidgetPlugin);
+        }
+        app.init_resource::<HandMenuSettings>();
+        app.init_resource::<SelectedShape>();
+        app.init_resource::<SelectedMaterial>();
+        app.init_resource::<SnapSettings>();
+        app.init_resource::<SurfaceSnapSettings>();
+        app.add_event::<HistoryStep>();
+        app.add_event::<SaveBlocks>();
+        app.add_event::<LoadBlocks>();
+        app.add_systems(Startup, spawn_hand_menu);
+        app.add_systems(
+            Update,
+            (
+                (place_hand_menu, sync_hand_menu)
+                    .after(HandsSet)
+                    .before(WidgetSet),
+                use_hand_menu.after(WidgetSet),
+            ),
+        );
+    }
+}
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct HandMenuSettings {
+    /// How directly the palm has to face the eyes to open the menu, as the cosine of the angle
+    /// between the palm normal and the direction to the eyes.
+    pub open_facing: f32,
+    /// The menu closes again once the palm faces the eyes less than this.
+    pub close_facing: f32,
+    /// How far in radians the palm can be from the center of view to open the menu.
+    pub gaze_angle: f32,
+    /// Distance in meters from the palm to the menu, along the palm normal.
+    pub offset: f32,
+    /// Size of a button in meters.
+    pub button_size: Vec2,
+}
+
+impl Default for HandMenuSettings {
+    fn default() -> Self {
+        Self {
+            open_facing: 0.7,
+            close_facing: 0.4,
+            gaze_angle: 35f32.to_radians(),
+            offset: 0.1,
+            button_size: Vec2::new(0.045, 0.018),
+        }
+    }
+}
+
+/// The root of the menu, its widgets are children.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct HandMenu {
+    pub open: bool,
+}
+
+/// What a menu widget does.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MenuAction {
+    /// Making cubes by pinching with both hands.
+    Build,
+    /// Repainting blocks with a finger dipped in the palette.
+    Paint,
+    /// Deleting blocks with a fist.
+    Delete,
+    Shape(ShapeKind),
+    Material(BlockMaterial),
+    GridSnap,
+    SurfaceSnap,
+    SizeReadout,
+    /// A slider from no gravity to twice Earth's.
+    Gravity,
+    Undo,
+    Redo,
+    Save,
+    Load,
+}
+
+/// Strongest gravity the menu can set, in multiples of Earth's.
+const MAX_GRAVITY: f32 = 2.0;
+
+impl MenuAction {
+    /// Rows of widgets from top to bottom.
+    pub fn layout() -> Vec<Vec<MenuAction>> {
+        vec![
+            vec![MenuAction::Build, MenuAction::Paint, MenuAction::Delete],
+            ShapeKind::ALL.map(MenuAction::Shape).to_vec(),
+            BlockMaterial::ALL.map(MenuAction::Material).to_vec(),
+            vec![
+                MenuAction::GridSnap,
+                MenuAction::SurfaceSnap,
+                MenuAction::SizeReadout,
+            ],
+            vec![MenuAction::Gravity],
+            vec![
+                MenuAction::Undo,
+                MenuAction::Redo,
+                MenuAction::Save,
+                MenuAction::Load,
+            ],
+        ]
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            MenuAction::Build => "Build".into(),
+            MenuAction::Paint => "Paint".into(),
+            MenuAction::Delete => "Delete".into(),
+            MenuAction::Shape(shape) => format!("{shape:?}"),
+            MenuAction::Material(material) => format!("{material:?}"),
+            MenuAction::GridSnap => "Grid".into(),
+            MenuAction::SurfaceSnap => "Surface".into(),
+            MenuAction::SizeReadout => "Size".into(),
+            MenuAction::Gravity => "Gravity".into(),
+            MenuAction::Undo => "Undo".into(),
+            MenuAction::Redo => "Redo".into(),
+            MenuAction::Save => "Save".into(),
+            MenuAction::Load => "Load".into(),
+        }
+    }
+}
+
+#[derive(Component, Copy, Clone, Debug)]
+pub struct MenuButton(pub MenuAction);
+
+/// Everything the menu widgets change.
+#[derive(SystemParam)]
+pub struct MenuTargets<'w> {
+    creation: Option<ResMut<'w, CubeCreationSettings>>,
+    palette: Option<ResMut<'w, PaletteSettings>>,
+    deletion: Option<ResMut<'w, DeletionSettings>>,
+    shape: ResMut<'w, SelectedShape>,
+    material: ResMut<'w, SelectedMaterial>,
+    snap: ResMut<'w, SnapSettings>,
+    surface_snap: ResMut<'w, SurfaceSnapSettings>,
+    readout: Option<ResMut<'w, DimensionReadoutSettings>>,
+    gravity: ResMut<'w, Gravity>,
+    history: EventWriter<'w, HistoryStep>,
+    save: EventWriter<'w, SaveBlocks>,
+    load: EventWriter<'w, LoadBlocks>,
+}
+
+impl MenuTargets<'_> {
+    /// Does what pressing the widget of `action` does, flipping settings that are toggled.
+    pub fn apply(&mut self, action: MenuAction) {
+        match action {
+            MenuAction::Build => {
+                if let Some(creation) = self.creation.as_mut() {
+                    creation.enabled = !creation.enabled;
+                }
+            }
+            MenuAction::Paint => {
+                if let Some(palette) = self.palette.as_mut() {
+                    palette.repaint = !palette.repaint;
+                }
+            }
+            MenuAction::Delete => {
+                if let Some(deletion) = self.deletion.as_mut() {
+                    deletion.enabled = !deletion.enabled;
+                }
+            }
+            MenuAction::Shape(shape) => **self.shape = shape,
+            MenuAction::Material(material) => **self.material = material,
+            MenuAction::GridSnap => self.snap.enabled = !self.snap.enabled,
+            MenuAction::SurfaceSnap => self.surface_snap.enabled = !self.surface_snap.enabled,
+            MenuAction::SizeReadout => {
+                if let Some(readout) = self.readout.as_mut() {
+                    readout.enabled = !readout.enabled;
+                }
+            }
+            MenuAction::Gravity => {}
+            MenuAction::Undo => {
+                self.history.send(HistoryStep::Undo);
+            }
+            MenuAction::Redo => {
+                self.history.send(HistoryStep::Redo);
+            }
+            MenuAction::Save => {
+                self.save.send(SaveBlocks);
+            }
+            MenuAction::Load => {
+                self.load.send(LoadBlocks);
+            }
+        }
+    }
+
+    /// Whether `action` is the current selection or a setting that is on.
+    pub fn is_active(&self, action: MenuAction) -> bool {
+        match action {
+            MenuAction::Build => self.creation.as_ref().is_some_and(|c| c.enabled),
+            MenuAction::Paint => self.palette.as_ref().is_some_and(|p| p.repaint),
+            MenuAction::Delete => self.deletion.as_ref().is_some_and(|d| d.enabled),
+            MenuAction::Shape(shape) => **self.shape == shape,
+            MenuAction::Material(material) => **self.material == material,
+            MenuAction::GridSnap => self.snap.enabled,
+            MenuAction::SurfaceSnap => self.surface_snap.enabled,
+            MenuAction::SizeReadout => self.readout.as_ref().is_some_and(|r| r.enabled),
+            MenuAction::Gravity => self.gravity.0 != Vec3::ZERO,
+            MenuAction::Undo | MenuAction::Redo | MenuAction::Save | MenuAction::Load => false,
+        }
+    }
+
+    /// Strength of gravity in multiples of Earth's.
+    pub fn gravity(&self) -> f32 {
+        self.gravity.0.length() / Gravity::default().0.length()
+    }
+
+    pub fn set_gravity(&mut self, strength: f32) {
+        *self.gravity = Gravity(Gravity::default().0 * strength);
+    }
+}
+
+fn spawn_hand_menu(mut commands: Commands, settings: Res<HandMenuSettings>) {
+    let size = settings.button_size;
+    let rows = MenuAction::layout();
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0) as f32;
+    let (column_step, row_step) = (size.x * 1.15, size.y * 1.5);
+    let panel_size = Vec2::new(column_step * columns, row_step * rows.len() as f32);
+    commands
+        .spawn((
+            HandMenu::default(),
+            Panel::new(panel_size, "Menu"),
+            SpatialBundle::default(),
+        ))
+        .with_children(|menu| {
+            for (row, actions) in rows.iter().enumerate() {
+                let y = ((rows.len() - 1) as f32 / 2.0 - row as f32) * row_step;
+                // Lone widgets span the whole row
+                let width = if actions.len() == 1 {
+                    column_step * (columns - 1.0) + size.x
+                } else {
+                    size.x
+                };
+                for (column, action) in actions.iter().enumerate() {
+                    let x = (column as f32 - (actions.len() - 1) as f32 / 2.0) * column_step;
+                    let mut widget = menu.spawn((
+                        MenuButton(*action),
+                        Widget::new(Vec2::new(width, size.y), action.label()),
+                        SpatialBundle::from_transform(Transform::from_xyz(x, y, 0.0)),
+                    ));
+                    match action {
+                        MenuAction::Gravity => widget.insert(Slider {
+                            value: 1.0,
+                            min: 0.0,
+                            max: MAX_GRAVITY,
+                            step: 0.1,
+                        }),
+                        MenuAction::Undo
+                        | MenuAction::Redo
+                        | MenuAction::Save
+                        | MenuAction::Load => widget.insert(PokeButton),
+                        _ => widget.insert(Toggle(false)),
+                    };
+                }
+            }
+        });
+}
+
+fn place_hand_menu(
+    settings: Res<HandMenuSettings>,
+    hands: Res<HandsState>,
+    cameras: Query<&GlobalTransform, With<Camera3d>>,
+    mut menus: Query<(
+        &mut HandMenu,
+        &mut Transform,
+        &mut GlobalTransform,
+        &mut Panel,
+        &Children,
+    )>,
+    mut widgets: Query<(&mut Widget, &Transform, &mut GlobalTransform), Without<HandMenu>>,
+) {
+    let palm = hands.left.joint(HandBone::Palm).zip(hands.left.palm_normal);
+    let eye = cameras.iter().next();
+
+    for (mut menu, mut transform, mut global_transform, mut panel, children) in &mut menus {
+        menu.open = match (palm, eye) {
+            (Some((palm, normal)), Some(eye)) => {
+                let to_eye = (eye.translation() - palm.translation).normalize_or_zero();
+                let facing = normal.dot(to_eye);
+                let looking = eye.forward().dot(-to_eye) >= settings.gaze_angle.cos();
+                if menu.open {
+                    facing >= settings.close_facing
+                } else {
+                    facing >= settings.open_facing && looking
+                }
+            }
+            _ => false,
+        };
+        panel.enabled = menu.open;
+        let mut children = widgets.iter_many_mut(children);
+        while let Some((mut widget, _, _)) = children.fetch_next() {
+            widget.enabled = menu.open;
+        }
+        let (Some((palm, normal)), Some(eye), true) = (palm, eye, menu.open) else {
+            continue;
+        };
+
+        // Float above the palm, upright and facing the viewer
+        let position = palm.translation + normal * settings.offset;
+        let away = position - eye.translation();
+        let away = Vec3::new(away.x, 0.0, away.z)
+            .try_normalize()
+            .unwrap_or(Vec3::NEG_Z);
+        *transform = Transform::from_translation(position).looking_to(away, Vec3::Y);
+
+        // Transforms are propagated after the widgets are poked, move the widgets along right away
+        // so they aren't poked where the menu was last frame
+        *global_transform = GlobalTransform::from(*transform);
+        let mut children = widgets.iter_many_mut(children);
+        while let Some((_, local, mut global)) = children.fetch_next() {
+            *global = global_transform.mul_transform(*local);
+        }
+    }
+}
+
+/// Shows the current selection and settings on the menu's toggles and slider.
+fn sync_hand_menu(
+    targets: MenuTargets,
+    mut widgets: Query<(
+        &MenuButton,
+        &mut Widget,
+        Option<&mut Toggle>,
+        Option<&mut Slider>,
+    )>,
+) {
+    for (button, mut widget, toggle, slider) in &mut widgets {
+        if let Some(mut toggle) = toggle {
+            let on = targets.is_active(button.0);
+            if toggle.0 != on {
+                toggle.0 = on;
+            }
+        }
+        if let Some(mut slider) = slider {
+            let gravity = targets.gravity();
+            if (slider.value - gravity).abs() > 1e-3 {
+                slider.value = gravity;
+            }
+            let label = format!("{} {gravity:.1} g", button.0.label());
+            if widget.label != label {
+                widget.label = label;
+            }
+        }
+    }
+}
+
+fn use_hand_menu(
+    mut targets: MenuTargets,
+    mut clicked: EventReader<ButtonClicked>,
+    mut toggled: EventReader<ToggleChanged>,
+    mut slid: EventReader<SliderChanged>,
+    buttons: Query<&MenuButton>,
+) {
+    let pressed = clicked
+        .read()
+        .map(|e| e.0)
+        .chain(toggled.read().map(|e| e.entity));
+    for button in buttons.iter_many(pressed) {
+        targets.apply(button.0);
+    }
+    for event in slid.read() {
+        if let Ok(MenuButton(MenuAction::Gravity)) = buttons.get(event.entity) {
+            targets.set_gravity(event.value);
+        }
+    }
+}
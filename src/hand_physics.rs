@@ -0,0 +1,244 @@
+//! Hands that push blocks around.
+//!
+//! Every tracked bone gets a kinematic sphere that follows it by velocity instead of being moved
+//! directly, so the physics sees how fast the hand moves and blocks take its momentum rather than
+//! getting pushed out of a teleporting collider. Capsules between the finger joints optionally fill
+//! the gaps for a continuous hand shape.
+
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_xpbd_3d::prelude::*;
+use bevy_xr::hands::{HandBone, HandBoneRadius, LeftHand, RightHand};
+
+use crate::hands::{Hand, ALL_BONES};
+use crate::layers::Layer;
+
+pub struct HandPhysicsPlugin;
+
+impl Plugin for HandPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HandPhysicsSettings>();
+        app.add_systems(Update, (spawn_hand_colliders, follow_hand_bones).chain());
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct HandPhysicsSettings {
+    /// The bones that get a collider, read when a hand is first tracked.
+    pub bones: Vec<HandBone>,
+    /// Add capsules between neighbouring finger joints that both have a collider.
+    pub capsules: bool,
+    /// A collider further than this in meters from its bone jumps there instead of sweeping
+    /// through everything on the way, e.g. when tracking comes back.
+    pub teleport_distance: f32,
+}
+
+impl Default for HandPhysicsSettings {
+    fn default() -> Self {
+        // The wrist and the knuckles inside the palm mostly get in the way of picking things up
+        let bones = ALL_BONES
+            .into_iter()
+            .filter(|bone| {
+                !matches!(
+                    bone,
+                    HandBone::Palm
+                        | HandBone::Wrist
+                        | HandBone::ThumbMetacarpal
+                        | HandBone::IndexMetacarpal
+                        | HandBone::MiddleMetacarpal
+                        | HandBone::RingMetacarpal
+                        | HandBone::LittleMetacarpal
+                )
+            })
+            .collect();
+        Self {
+            bones,
+            capsules: false,
+            teleport_distance: 0.3,
+        }
+    }
+}
+
+/// The joints connected by capsules, along each finger from the knuckle to the tip.
+pub const FINGER_SEGMENTS: [(HandBone, HandBone); 19] = [
+    (HandBone::ThumbMetacarpal, HandBone::ThumbProximal),
+    (HandBone::ThumbProximal, HandBone::ThumbDistal),
+    (HandBone::ThumbDistal, HandBone::ThumbTip),
+    (HandBone::IndexMetacarpal, HandBone::IndexProximal),
+    (HandBone::IndexProximal, HandBone::IndexIntermediate),
+    (HandBone::IndexIntermediate, HandBone::IndexDistal),
+    (HandBone::IndexDistal, HandBone::IndexTip),
+    (HandBone::MiddleMetacarpal, HandBone::MiddleProximal),
+    (HandBone::MiddleProximal, HandBone::MiddleIntermediate),
+    (HandBone::MiddleIntermediate, HandBone::MiddleDistal),
+    (HandBone::MiddleDistal, HandBone::MiddleTip),
+    (HandBone::RingMetacarpal, HandBone::RingProximal),
+    (HandBone::RingProximal, HandBone::RingIntermediate),
+    (HandBone::RingIntermediate, HandBone::RingDistal),
+    (HandBone::RingDistal, HandBone::RingTip),
+    (HandBone::LittleMetacarpal, HandBone::LittleProximal),
+    (HandBone::LittleProximal, HandBone::LittleIntermediate),
+    (HandBone::LittleIntermediate, HandBone::LittleDistal),
+    (HandBone::LittleDistal, HandBone::LittleTip),
+];
+
+/// A kinematic collider following a hand bone, or the segment between two bones.
+#[derive(Component, Copy, Clone, Debug)]
+pub struct HandCollider {
+    pub hand: Hand,
+    pub bone: HandBone,
+    /// The bone at the other end of the capsule, a sphere if `None`.
+    pub to: Option<HandBone>,
+    radius: f32,
+    length: f32,
+}
+
+/// Pose and radius of every bone of one hand, indexed by the bone.
+type BonePoses = [Option<(Vec3, Quat, f32)>; 26];
+
+fn spawn_hand_colliders(
+    mut commands: Commands,
+    settings: Res<HandPhysicsSettings>,
+    bones: Query<(&HandBone, Has<LeftHand>, Has<RightHand>), With<HandBoneRadius>>,
+    mut spawned: Local<HashSet<Hand>>,
+) {
+    for hand in [Hand::Left, Hand::Right] {
+        if spawned.contains(&hand) {
+            continue;
+        }
+        let hand_bones: Vec<HandBone> = bones
+            .iter()
+            .filter(|(_, left, right)| if hand == Hand::Left { *left } else { *right })
+            .map(|(bone, _, _)| *bone)
+            .collect();
+        if hand_bones.is_empty() {
+            continue;
+        }
+        spawned.insert(hand);
+
+        let enabled = |bone: &HandBone| settings.bones.contains(bone);
+        let spheres = hand_bones
+            .into_iter()
+            .filter(enabled)
+            .map(|bone| (bone, None));
+        let capsules = FINGER_SEGMENTS
+            .into_iter()
+            .filter(|(from, to)| settings.capsules && enabled(from) && enabled(to))
+            .map(|(from, to)| (from, Some(to)));
+        for (bone, to) in spheres.chain(capsules) {
+            commands.spawn((
+                HandCollider {
+                    hand,
+                    bone,
+                    to,
+                    radius: 0.0,
+                    length: 0.0,
+                },
+                RigidBody::Kinematic,
+                Collider::sphere(0.01),
+                Layer::Hands.collision_layers(),
+                TransformBundle::default(),
+            ));
+        }
+    }
+}
+
+/// Angular velocity turning `from` into `to` within `delta` seconds, the short way around.
+fn angular_velocity(from: Quat, to: Quat, delta: f32) -> Vec3 {
+    let (axis, angle) = (to * from.inverse()).to_axis_angle();
+    let angle = if angle > std::f32::consts::PI {
+        angle - std::f32::consts::TAU
+    } else {
+        angle
+    };
+    axis * angle / delta
+}
+
+#[allow(clippy::type_complexity)]
+fn follow_hand_bones(
+    time: Res<Time>,
+    settings: Res<HandPhysicsSettings>,
+    bones: Query<(
+        &HandBone,
+        &GlobalTransform,
+        &HandBoneRadius,
+        Has<LeftHand>,
+        Has<RightHand>,
+    )>,
+    mut colliders: Query<(
+        &mut HandCollider,
+        &mut Collider,
+        &mut Position,
+        &mut Rotation,
+        &mut LinearVelocity,
+        &mut AngularVelocity,
+    )>,
+) {
+    let delta = time.delta_seconds();
+    if delta <= 0.0 {
+        return;
+    }
+    let mut poses: [BonePoses; 2] = [[None; 26]; 2];
+    for (bone, transform, radius, left, right) in &bones {
+        let hand = if left {
+            0
+        } else if right {
+            1
+        } else {
+            continue;
+        };
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        poses[hand][*bone as usize] = Some((translation, rotation, radius.0));
+    }
+
+    for (mut hand_collider, mut collider, mut position, mut rotation, mut linear, mut angular) in
+        &mut colliders
+    {
+        let pose = &poses[hand_collider.hand as usize];
+        let target =
+            pose[hand_collider.bone as usize].and_then(|(start, start_rotation, radius)| {
+                match hand_collider.to {
+                    None => Some((start, start_rotation, radius, 0.0)),
+                    Some(to) => pose[to as usize].map(|(end, _, end_radius)| {
+                        let segment = end - start;
+                        (
+                            start + segment / 2.0,
+                            segment.try_normalize().map_or(start_rotation, |axis| {
+                                Quat::from_rotation_arc(Vec3::Y, axis)
+                            }),
+                            radius.min(end_radius),
+                            segment.length(),
+                        )
+                    }),
+                }
+            });
+        // Hold still while the bone isn't tracked instead of flying on with the last velocity
+        let Some((target, target_rotation, radius, length)) = target else {
+            linear.0 = Vec3::ZERO;
+            angular.0 = Vec3::ZERO;
+            continue;
+        };
+
+        // Bones don't change size, but their first tracked frames can be off
+        if (radius - hand_collider.radius).abs() > 0.001
+            || (length - hand_collider.length).abs() > 0.002
+        {
+            hand_collider.radius = radius;
+            hand_collider.length = length;
+            *collider = match hand_collider.to {
+                None => Collider::sphere(radius),
+                Some(_) => Collider::capsule(length, radius),
+            };
+        }
+
+        if position.0.distance(target) > settings.teleport_distance {
+            position.0 = target;
+            rotation.0 = target_rotation;
+            linear.0 = Vec3::ZERO;
+            angular.0 = Vec3::ZERO;
+            continue;
+        }
+        linear.0 = (target - position.0) / delta;
+        angular.0 = angular_velocity(rotation.0, target_rotation, delta);
+    }
+}
@@ -0,0 +1,393 @@
+//! Recording hand tracking to disk and replaying it later.
+//!
+//! [`HandRecordingPlugin`] appends the pose of every tracked hand bone to a file each frame,
+//! [`HandReplayPlugin`] spawns bone entities and moves them along a recording, so real pinch
+//! sessions captured on device can be replayed in CI against the gesture systems.
+//!
+//! The file is a little endian stream of a header (`VRBH` followed by a version byte) and frames.
+//! A frame is its time in seconds (`f32`) and bone count (`u16`), followed by that many bones of
+//! hand (`u8`, 0 left, 1 right), bone (`u8`, index into [`ALL_BONES`]), radius (`f32`),
+//! translation (3 `f32`) and rotation (4 `f32`).
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::window::ApplicationLifetime;
+use bevy_xr::hands::{HandBone, HandBoneRadius, LeftHand, RightHand};
+
+use crate::hands::{Hand, ALL_BONES};
+
+const MAGIC: &[u8; 4] = b"VRBH";
+const VERSION: u8 = 1;
+
+/// A single bone pose in a [`HandFrame`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RecordedBone {
+    pub hand: Hand,
+    pub bone: HandBone,
+    pub radius: f32,
+    pub transform: Transform,
+}
+
+/// Every tracked bone at one point in time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HandFrame {
+    /// Seconds since the recording started.
+    pub time: f32,
+    pub bones: Vec<RecordedBone>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HandRecording {
+    pub frames: Vec<HandFrame>,
+}
+
+impl HandRecording {
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        Self::read(BufReader::new(File::open(path.into())?))
+    }
+
+    pub fn save(&self, path: impl Into<PathBuf>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path.into())?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn read(mut reader: impl Read) -> io::Result<Self> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC || read_u8(&mut reader)? != VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a hand recording",
+            ));
+        }
+
+        let mut frames = Vec::new();
+        loop {
+            // Only running out of data between frames is the end of the recording, anything
+            // else is a truncated file
+            let mut time = [0; 4];
+            match reader.read_exact(&mut time[..1]) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+            reader.read_exact(&mut time[1..])?;
+            let time = f32::from_le_bytes(time);
+            let mut count = [0; 2];
+            reader.read_exact(&mut count)?;
+            let bones = (0..u16::from_le_bytes(count))
+                .map(|_| read_bone(&mut reader))
+                .collect::<io::Result<_>>()?;
+            frames.push(HandFrame { time, bones });
+        }
+        Ok(Self { frames })
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        write_header(&mut writer)?;
+        for frame in &self.frames {
+            write_frame(&mut writer, frame)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the last frame at or before `time`.
+    pub fn frame_at(&self, time: f32) -> Option<&HandFrame> {
+        let next = self.frames.partition_point(|frame| frame.time <= time);
+        self.frames.get(next.checked_sub(1)?)
+    }
+
+    /// Length of the recording in seconds.
+    pub fn duration(&self) -> f32 {
+        self.frames.last().map_or(0.0, |frame| frame.time)
+    }
+}
+
+fn write_header(writer: &mut impl Write) -> io::Result<()> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[VERSION])
+}
+
+fn write_frame(writer: &mut impl Write, frame: &HandFrame) -> io::Result<()> {
+    writer.write_all(&frame.time.to_le_bytes())?;
+    writer.write_all(&(frame.bones.len() as u16).to_le_bytes())?;
+    for bone in &frame.bones {
+        let hand = match bone.hand {
+            Hand::Left => 0,
+            Hand::Right => 1,
+        };
+        writer.write_all(&[hand, bone.bone as u8])?;
+        writer.write_all(&bone.radius.to_le_bytes())?;
+        for value in bone.transform.translation.to_array() {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+        for value in bone.transform.rotation.to_array() {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+fn read_bone(reader: &mut impl Read) -> io::Result<RecordedBone> {
+    let hand = match read_u8(reader)? {
+        0 => Hand::Left,
+        1 => Hand::Right,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid hand")),
+    };
+    let Some(bone) = ALL_BONES.get(read_u8(reader)? as usize).copied() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid bone"));
+    };
+    let radius = read_f32(reader)?;
+    let mut values = [0.0; 7];
+    for value in &mut values {
+        *value = read_f32(reader)?;
+    }
+    Ok(RecordedBone {
+        hand,
+        bone,
+        radius,
+        transform: Transform::from_translation(Vec3::from_slice(&values[..3]))
+            .with_rotation(Quat::from_slice(&values[3..])),
+    })
+}
+
+fn read_u8(reader: &mut impl Read) -> io::Result<u8> {
+    let mut bytes = [0; 1];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes[0])
+}
+
+fn read_f32(reader: &mut impl Read) -> io::Result<f32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(f32::from_le_bytes(bytes))
+}
+
+/// Records the pose of every hand bone to `path` each frame.
+pub struct HandRecordingPlugin {
+    pub path: PathBuf,
+}
+
+impl Plugin for HandRecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HandRecorder {
+            path: self.path.clone(),
+            writer: None,
+            start: None,
+        });
+        app.add_event::<ApplicationLifetime>();
+        app.add_systems(Startup, start_recording);
+        app.add_systems(Last, (record_hands, flush_recording));
+    }
+}
+
+#[derive(Resource)]
+pub struct HandRecorder {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    start: Option<f32>,
+}
+
+impl HandRecorder {
+    /// Stops recording, flushing everything written so far.
+    pub fn stop(&mut self) {
+        if let Some(mut writer) = self.writer.take() {
+            if let Err(e) = writer.flush() {
+                error!("unable to finish hand recording: {e}");
+            }
+        }
+    }
+}
+
+fn start_recording(mut recorder: ResMut<HandRecorder>) {
+    let writer = File::create(&recorder.path)
+        .map(BufWriter::new)
+        .and_then(|mut writer| write_header(&mut writer).map(|_| writer));
+    match writer {
+        Ok(writer) => recorder.writer = Some(writer),
+        Err(e) => error!("unable to start hand recording at {:?}: {e}", recorder.path),
+    }
+}
+
+fn record_hands(
+    time: Res<Time>,
+    mut recorder: ResMut<HandRecorder>,
+    bones: Query<(
+        &HandBone,
+        &HandBoneRadius,
+        &GlobalTransform,
+        Has<LeftHand>,
+        Has<RightHand>,
+    )>,
+) {
+    let recorder = recorder.as_mut();
+    let Some(writer) = recorder.writer.as_mut() else {
+        return;
+    };
+    let start = *recorder.start.get_or_insert(time.elapsed_seconds());
+    let frame = HandFrame {
+        time: time.elapsed_seconds() - start,
+        bones: bones
+            .iter()
+            .filter_map(|(bone, radius, transform, left, right)| {
+                let hand = match (left, right) {
+                    (true, false) => Hand::Left,
+                    (false, true) => Hand::Right,
+                    _ => return None,
+                };
+                Some(RecordedBone {
+                    hand,
+                    bone: *bone,
+                    radius: radius.0,
+                    transform: transform.compute_transform(),
+                })
+            })
+            .collect(),
+    };
+    if let Err(e) = write_frame(writer, &frame) {
+        error!("unable to write hand recording frame, stopping: {e}");
+        recorder.writer = None;
+    }
+}
+
+fn flush_recording(
+    mut recorder: ResMut<HandRecorder>,
+    mut exit: EventReader<AppExit>,
+    mut lifetime: EventReader<ApplicationLifetime>,
+) {
+    let suspended = lifetime
+        .read()
+        .any(|e| matches!(e, ApplicationLifetime::Suspended));
+    if exit.read().count() > 0 {
+        recorder.stop();
+    } else if suspended {
+        if let Some(Err(e)) = recorder.writer.as_mut().map(|writer| writer.flush()) {
+            error!("unable to flush hand recording: {e}");
+        }
+    }
+}
+
+/// Spawns bone entities for every hand bone in a recording and moves them along it.
+pub struct HandReplayPlugin {
+    pub recording: HandRecording,
+    /// Replace `recording` with this file at startup, a file that can't be read is logged and
+    /// replays nothing.
+    pub path: Option<PathBuf>,
+    /// Start over once the end of the recording is reached.
+    pub looping: bool,
+}
+
+impl HandReplayPlugin {
+    pub fn from_file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            recording: HandRecording::default(),
+            path: Some(path.into()),
+            looping: false,
+        }
+    }
+}
+
+impl Plugin for HandReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HandReplay {
+            recording: self.recording.clone(),
+            looping: self.looping,
+            start: None,
+            finished: false,
+        });
+        if let Some(path) = self.path.clone() {
+            app.add_systems(Startup, load_replay(path).before(spawn_replayed_bones));
+        }
+        app.add_systems(Startup, spawn_replayed_bones);
+        app.add_systems(PreUpdate, replay_hands);
+    }
+}
+
+fn load_replay(path: PathBuf) -> impl FnMut(ResMut<HandReplay>) + Send + Sync + 'static {
+    move |mut replay| match HandRecording::load(&path) {
+        Ok(recording) => replay.recording = recording,
+        Err(e) => error!("unable to load hand recording from {path:?}: {e}"),
+    }
+}
+
+#[derive(Resource)]
+pub struct HandReplay {
+    recording: HandRecording,
+    looping: bool,
+    start: Option<f32>,
+    finished: bool,
+}
+
+impl HandReplay {
+    /// Whether the last frame of a non looping replay has been reached.
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Marks a bone entity driven by [`HandReplayPlugin`].
+#[derive(Component, Copy, Clone, Debug)]
+pub struct ReplayedBone(pub Hand);
+
+fn spawn_replayed_bones(mut commands: Commands, replay: Res<HandReplay>) {
+    let mut spawned: Vec<(Hand, HandBone)> = Vec::new();
+    for bone in replay.recording.frames.iter().flat_map(|f| &f.bones) {
+        if spawned.contains(&(bone.hand, bone.bone)) {
+            continue;
+        }
+        spawned.push((bone.hand, bone.bone));
+        let mut entity = commands.spawn((
+            bone.bone,
+            ReplayedBone(bone.hand),
+            HandBoneRadius(bone.radius),
+            SpatialBundle::from_transform(bone.transform),
+        ));
+        match bone.hand {
+            Hand::Left => entity.insert(LeftHand),
+            Hand::Right => entity.insert(RightHand),
+        };
+    }
+}
+
+fn replay_hands(
+    time: Res<Time>,
+    mut replay: ResMut<HandReplay>,
+    mut bones: Query<(
+        &HandBone,
+        &ReplayedBone,
+        &mut Transform,
+        &mut GlobalTransform,
+    )>,
+) {
+    let replay = replay.as_mut();
+    let start = *replay.start.get_or_insert(time.elapsed_seconds());
+    let mut elapsed = time.elapsed_seconds() - start;
+    let duration = replay.recording.duration();
+    if elapsed > duration {
+        if replay.looping && duration > 0.0 {
+            elapsed %= duration;
+        } else {
+            replay.finished = true;
+        }
+    }
+
+    let Some(frame) = replay.recording.frame_at(elapsed) else {
+        return;
+    };
+    for (bone, replayed, mut transform, mut global_transform) in &mut bones {
+        let Some(recorded) = frame
+            .bones
+            .iter()
+            .find(|b| b.hand == replayed.0 && b.bone == *bone)
+        else {
+            continue;
+        };
+        *transform = recorded.transform;
+        *global_transform = GlobalTransform::from(recorded.transform);
+    }
+}
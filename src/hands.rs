@@ -0,0 +1,222 @@
+//! Shared helpers for working with `bevy_xr` hand bones.
+//!
+//! [`HandsPlugin`] gathers the bones of both hands into the [`HandsState`] resource once per frame,
+//! with the pinch and grab strength worked out, so gestures read one resource instead of scanning
+//! the bone entities. Other input, like [motion controllers](crate::controllers), fills in the
+//! hands it stands in for during [`HandInputSet`], so every tool reading [`HandsState`] works with
+//! either.
+
+use bevy::prelude::*;
+use bevy_xr::hands::{HandBone, LeftHand, RightHand};
+
+use crate::pinch::{PinchPlugin, PinchSet, PinchState};
+
+pub struct HandsPlugin;
+
+impl Plugin for HandsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<PinchPlugin>() {
+            app.add_plugins(PinchPlugin);
+        }
+        app.init_resource::<HandsSettings>();
+        app.init_resource::<HandsState>();
+        app.configure_sets(Update, HandInputSet.in_set(HandsSet));
+        app.add_systems(
+            Update,
+            update_hands_state
+                .in_set(HandsSet)
+                .before(HandInputSet)
+                .after(PinchSet),
+        );
+    }
+}
+
+/// Updates [`HandsState`], order systems reading it after this set.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HandsSet;
+
+/// Part of [`HandsSet`] after the tracked bones have been read, input standing in for a hand
+/// overwrites its [`HandState`] here.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HandInputSet;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// Every [`HandBone`] in declaration order, so `ALL_BONES[bone as usize] == bone`.
+pub const ALL_BONES: [HandBone; 26] = [
+    HandBone::Palm,
+    HandBone::Wrist,
+    HandBone::ThumbMetacarpal,
+    HandBone::ThumbProximal,
+    HandBone::ThumbDistal,
+    HandBone::ThumbTip,
+    HandBone::IndexMetacarpal,
+    HandBone::IndexProximal,
+    HandBone::IndexIntermediate,
+    HandBone::IndexDistal,
+    HandBone::IndexTip,
+    HandBone::MiddleMetacarpal,
+    HandBone::MiddleProximal,
+    HandBone::MiddleIntermediate,
+    HandBone::MiddleDistal,
+    HandBone::MiddleTip,
+    HandBone::RingMetacarpal,
+    HandBone::RingProximal,
+    HandBone::RingIntermediate,
+    HandBone::RingDistal,
+    HandBone::RingTip,
+    HandBone::LittleMetacarpal,
+    HandBone::LittleProximal,
+    HandBone::LittleIntermediate,
+    HandBone::LittleDistal,
+    HandBone::LittleTip,
+];
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct HandsSettings {
+    /// Thumb to index tip distance in meters at which the pinch strength is one.
+    pub pinch_closed: f32,
+    /// Thumb to index tip distance in meters at which the pinch strength is zero.
+    pub pinch_open: f32,
+    /// Average finger tip to palm distance in meters at which the grab strength is one.
+    pub grab_closed: f32,
+    /// Average finger tip to palm distance in meters at which the grab strength is zero.
+    pub grab_open: f32,
+}
+
+impl Default for HandsSettings {
+    fn default() -> Self {
+        Self {
+            pinch_closed: 0.015,
+            pinch_open: 0.08,
+            grab_closed: 0.05,
+            grab_open: 0.1,
+        }
+    }
+}
+
+/// What one hand is doing this frame.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct HandState {
+    /// World space pose of every joint indexed by [`HandBone`], `None` for joints without a pose.
+    pub joints: [Option<Transform>; 26],
+    /// Whether the runtime moved the hand's bones since the last frame. Runtimes stop updating the
+    /// bones of a hand they lose track of, its joints are all `None` then.
+    pub tracked: bool,
+    /// Whether the hand is pinching, its [`PinchState`] is held or it pulls a controller's trigger.
+    pub pinching: bool,
+    /// How close thumb and index tip are, from 0 when apart to 1 when touching.
+    pub pinch_strength: f32,
+    /// How far the fingers are curled into the palm, from 0 when open to 1 for a fist.
+    pub grab_strength: f32,
+    /// Direction the palm faces, `None` without a tracked palm.
+    pub palm_normal: Option<Vec3>,
+    /// Where thumb and index meet, halfway between their tips and oriented like the palm. `None`
+    /// without tracked thumb and index tips.
+    pub pinch_point: Option<Transform>,
+}
+
+impl HandState {
+    pub fn joint(&self, bone: HandBone) -> Option<Transform> {
+        self.joints[bone as usize]
+    }
+}
+
+/// Both hands this frame.
+#[derive(Resource, Copy, Clone, Debug, Default, PartialEq)]
+pub struct HandsState {
+    pub left: HandState,
+    pub right: HandState,
+}
+
+impl HandsState {
+    pub fn hand(&self, hand: Hand) -> &HandState {
+        match hand {
+            Hand::Left => &self.left,
+            Hand::Right => &self.right,
+        }
+    }
+
+    pub fn hand_mut(&mut self, hand: Hand) -> &mut HandState {
+        match hand {
+            Hand::Left => &mut self.left,
+            Hand::Right => &mut self.right,
+        }
+    }
+}
+
+/// Maps `distance` to 1 at `closed` and 0 at `open`.
+fn strength(distance: f32, closed: f32, open: f32) -> f32 {
+    (1.0 - (distance - closed) / (open - closed)).clamp(0.0, 1.0)
+}
+
+#[allow(clippy::type_complexity)]
+fn update_hands_state(
+    settings: Res<HandsSettings>,
+    mut state: ResMut<HandsState>,
+    bones: Query<(
+        &HandBone,
+        Ref<GlobalTransform>,
+        Has<LeftHand>,
+        Has<RightHand>,
+    )>,
+    pinches: Query<&PinchState>,
+) {
+    let mut hands = HandsState::default();
+    for (bone, transform, left, right) in &bones {
+        // A bone left where it was is stale, not a hand holding perfectly still
+        if !transform.is_changed() {
+            continue;
+        }
+        let hand = match (left, right) {
+            (true, false) => Hand::Left,
+            (false, true) => Hand::Right,
+            _ => continue,
+        };
+        let hand = hands.hand_mut(hand);
+        hand.joints[*bone as usize] = Some(transform.compute_transform());
+        hand.tracked = true;
+    }
+    for pinch in &pinches {
+        let hand = hands.hand_mut(pinch.hand);
+        hand.pinching |= hand.tracked && pinch.is_held();
+    }
+
+    for hand in [&mut hands.left, &mut hands.right] {
+        let joints = hand.joints;
+        let joint = |bone: HandBone| joints[bone as usize].map(|transform| transform.translation);
+        let palm = joints[HandBone::Palm as usize];
+        if let (Some(thumb), Some(index)) = (joint(HandBone::ThumbTip), joint(HandBone::IndexTip)) {
+            hand.pinch_strength = strength(
+                thumb.distance(index),
+                settings.pinch_closed,
+                settings.pinch_open,
+            );
+            hand.pinch_point = Some(Transform {
+                translation: index.lerp(thumb, 0.5),
+                rotation: palm.map_or(Quat::IDENTITY, |palm| palm.rotation),
+                scale: Vec3::ONE,
+            });
+        }
+        if let Some(palm) = palm {
+            let tips: Vec<f32> = [HandBone::MiddleTip, HandBone::RingTip, HandBone::LittleTip]
+                .into_iter()
+                .filter_map(joint)
+                .map(|tip| tip.distance(palm.translation))
+                .collect();
+            if !tips.is_empty() {
+                let average = tips.iter().sum::<f32>() / tips.len() as f32;
+                hand.grab_strength = strength(average, settings.grab_closed, settings.grab_open);
+            }
+        }
+        // OpenXR palms have +Y on the back of the hand
+        hand.palm_normal = palm.map(|palm| *palm.down());
+    }
+    if *state != hands {
+        *state = hands;
+    }
+}
@@ -0,0 +1,318 @@
+//! Undo and redo for everything the user does to blocks.
+//!
+//! [`BlockHistory`] records made, deleted, moved and repainted blocks. [`HistoryStep`] events step
+//! through it in the order they were sent, as does holding thumb and middle finger tip together
+//! with the index stretched out: the left hand undoes, the right hand redoes. Blocks are restored
+//! with their exact transform and material.
+
+use bevy::ecs::event::ManualEventReader;
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use bevy_xr::hands::HandBone;
+
+use crate::block::{
+    Block, BlockDeleted, BlockId, BlockMoved, BlockRepainted, BlockSnapshot, BlockSpawned,
+};
+use crate::hands::{Hand, HandsPlugin, HandsSet, HandsState};
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<HandsPlugin>() {
+            app.add_plugins(HandsPlugin);
+        }
+        app.init_resource::<BlockHistory>();
+        app.init_resource::<HistoryGestureSettings>();
+        app.add_event::<BlockSpawned>();
+        app.add_event::<BlockMoved>();
+        app.add_event::<BlockDeleted>();
+        app.add_event::<BlockRepainted>();
+        app.add_event::<HistoryStep>();
+        app.add_systems(Update, undo_redo_gesture.after(HandsSet));
+        app.add_systems(PostUpdate, (record_history, undo_redo).chain());
+    }
+}
+
+/// Steps through the [`BlockHistory`], one event per step.
+#[derive(Event, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HistoryStep {
+    /// Undoes the last change to the blocks.
+    Undo,
+    /// Redoes the last undone change to the blocks.
+    Redo,
+}
+
+#[derive(Clone, Debug)]
+pub enum HistoryEntry {
+    Spawned(BlockSnapshot),
+    Deleted(BlockSnapshot),
+    Moved {
+        id: BlockId,
+        from: Transform,
+        to: Transform,
+    },
+    Repainted {
+        id: BlockId,
+        from: Handle<StandardMaterial>,
+        to: Handle<StandardMaterial>,
+    },
+}
+
+impl HistoryEntry {
+    fn inverse(&self) -> Self {
+        match self {
+            HistoryEntry::Spawned(snapshot) => HistoryEntry::Deleted(snapshot.clone()),
+            HistoryEntry::Deleted(snapshot) => HistoryEntry::Spawned(snapshot.clone()),
+            HistoryEntry::Moved { id, from, to } => HistoryEntry::Moved {
+                id: *id,
+                from: *to,
+                to: *from,
+            },
+            HistoryEntry::Repainted { id, from, to } => HistoryEntry::Repainted {
+                id: *id,
+                from: to.clone(),
+                to: from.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct BlockHistory {
+    undo: Vec<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+    /// How many changes can be undone, the oldest are forgotten first.
+    pub limit: usize,
+}
+
+impl Default for BlockHistory {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            limit: 100,
+        }
+    }
+}
+
+impl BlockHistory {
+    /// Records a change the user just made, which can't be redone past anymore.
+    pub fn push(&mut self, entry: HistoryEntry) {
+        self.redo.clear();
+        self.undo.push(entry);
+        if self.undo.len() > self.limit {
+            let excess = self.undo.len() - self.limit;
+            self.undo.drain(..excess);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct HistoryGestureSettings {
+    /// Thumb to middle tip distance in meters below which the gesture starts engaging.
+    pub engage_distance: f32,
+    /// Thumb to middle tip distance in meters the fingers have to open to before it triggers again.
+    pub release_distance: f32,
+    /// Seconds thumb and middle tip have to stay together before the gesture triggers.
+    pub dwell: f32,
+    /// Thumb to index tip distance in meters the index has to be stretched beyond, so making a
+    /// fist doesn't trigger the gesture.
+    pub index_open_distance: f32,
+}
+
+impl Default for HistoryGestureSettings {
+    fn default() -> Self {
+        Self {
+            engage_distance: 0.02,
+            release_distance: 0.04,
+            dwell: 0.15,
+            index_open_distance: 0.06,
+        }
+    }
+}
+
+/// The undo and redo gesture of one hand.
+#[derive(Copy, Clone, Debug, Default)]
+enum GestureState {
+    #[default]
+    Open,
+    /// Thumb and middle tip are together since this time.
+    Engaging(f32),
+    /// Triggered, waiting for the fingers to open again.
+    Triggered,
+}
+
+fn undo_redo_gesture(
+    time: Res<Time>,
+    settings: Res<HistoryGestureSettings>,
+    hands: Res<HandsState>,
+    mut gestures: Local<[GestureState; 2]>,
+    mut steps: EventWriter<HistoryStep>,
+) {
+    let now = time.elapsed_seconds();
+    for (i, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let find = |bone: HandBone| hands.hand(hand).joint(bone).map(|joint| joint.translation);
+        let (Some(thumb), Some(index), Some(middle)) = (
+            find(HandBone::ThumbTip),
+            find(HandBone::IndexTip),
+            find(HandBone::MiddleTip),
+        ) else {
+            gestures[i] = GestureState::Open;
+            continue;
+        };
+        let distance = thumb.distance(middle);
+        let closed = distance <= settings.engage_distance
+            && thumb.distance(index) >= settings.index_open_distance;
+        gestures[i] = match gestures[i] {
+            GestureState::Triggered if distance < settings.release_distance => {
+                GestureState::Triggered
+            }
+            _ if !closed => GestureState::Open,
+            GestureState::Engaging(since) if now - since >= settings.dwell => {
+                steps.send(match hand {
+                    Hand::Left => HistoryStep::Undo,
+                    Hand::Right => HistoryStep::Redo,
+                });
+                GestureState::Triggered
+            }
+            GestureState::Engaging(since) => GestureState::Engaging(since),
+            GestureState::Open | GestureState::Triggered => GestureState::Engaging(now),
+        };
+    }
+}
+
+fn record_history(
+    mut history: ResMut<BlockHistory>,
+    mut spawned: EventReader<BlockSpawned>,
+    mut moved: EventReader<BlockMoved>,
+    mut deleted: EventReader<BlockDeleted>,
+    mut repainted: EventReader<BlockRepainted>,
+    blocks: Query<(&BlockId, &Block, &Transform, &Handle<StandardMaterial>)>,
+) {
+    for BlockSpawned(entity) in spawned.read() {
+        let Ok((id, block, transform, material)) = blocks.get(*entity) else {
+            continue;
+        };
+        history.push(HistoryEntry::Spawned(BlockSnapshot {
+            id: *id,
+            block: *block,
+            transform: *transform,
+            material: material.clone(),
+        }));
+    }
+    for BlockMoved { id, from, to } in moved.read() {
+        history.push(HistoryEntry::Moved {
+            id: *id,
+            from: *from,
+            to: *to,
+        });
+    }
+    for BlockRepainted { id, from, to } in repainted.read() {
+        history.push(HistoryEntry::Repainted {
+            id: *id,
+            from: from.clone(),
+            to: to.clone(),
+        });
+    }
+    for BlockDeleted(snapshot) in deleted.read() {
+        history.push(HistoryEntry::Deleted(snapshot.clone()));
+    }
+}
+
+type UndoRedoState = SystemState<(
+    Commands<'static, 'static>,
+    ResMut<'static, BlockHistory>,
+    ResMut<'static, Assets<Mesh>>,
+    Query<
+        'static,
+        'static,
+        (
+            Entity,
+            &'static BlockId,
+            &'static mut Transform,
+            &'static mut LinearVelocity,
+            &'static mut AngularVelocity,
+            &'static mut Handle<StandardMaterial>,
+        ),
+    >,
+)>;
+
+/// Applies every [`HistoryStep`] in the order it was sent. Each step's commands are applied
+/// before the next one, so a step can change a block an earlier one respawned.
+fn undo_redo(
+    world: &mut World,
+    state: &mut UndoRedoState,
+    mut reader: Local<ManualEventReader<HistoryStep>>,
+) {
+    let steps: Vec<_> = reader
+        .read(world.resource::<Events<HistoryStep>>())
+        .copied()
+        .collect();
+    for step in steps {
+        let (mut commands, mut history, mut meshes, mut blocks) = state.get_mut(world);
+        let history = history.as_mut();
+        let (from, to) = match step {
+            HistoryStep::Undo => (&mut history.undo, &mut history.redo),
+            HistoryStep::Redo => (&mut history.redo, &mut history.undo),
+        };
+        let Some(entry) = from.pop() else { continue };
+        // Undoing applies the inverse of the entry, redoing applies it again
+        let change = match step {
+            HistoryStep::Undo => entry.inverse(),
+            HistoryStep::Redo => entry.clone(),
+        };
+        match &change {
+            HistoryEntry::Spawned(snapshot) => {
+                snapshot.respawn(&mut commands, &mut meshes);
+            }
+            HistoryEntry::Deleted(snapshot) => {
+                for (entity, id, ..) in &blocks {
+                    if *id == snapshot.id {
+                        commands.entity(entity).despawn_recursive();
+                    }
+                }
+            }
+            HistoryEntry::Moved {
+                id: moved,
+                to: target,
+                ..
+            } => {
+                for (_, id, mut transform, mut linear, mut angular, _) in &mut blocks {
+                    if id == moved {
+                        *transform = *target;
+                        linear.0 = Vec3::ZERO;
+                        angular.0 = Vec3::ZERO;
+                    }
+                }
+            }
+            HistoryEntry::Repainted {
+                id: repainted,
+                to: target,
+                ..
+            } => {
+                for (.., id, _, _, _, mut material) in &mut blocks {
+                    if id == repainted {
+                        *material = target.clone();
+                    }
+                }
+            }
+        }
+        to.push(entry);
+        state.apply(world);
+    }
+}
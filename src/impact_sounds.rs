@@ -0,0 +1,261 @@
+//! Sounds for things hitting each other.
+//!
+//! Every new contact reported by the physics [`Collision`] events plays a hit at the contact point,
+//! as loud as the bodies were moving into each other along the contact normal there. Contacts that
+//! were already touching last frame stay silent, so resting and sliding blocks don't buzz, and
+//! sensors such as dissolving blocks never sound. Each pair of colliders has a short cooldown so a
+//! rattling pair doesn't retrigger every frame, but any number of pairs can sound in the same
+//! frame, so a toppling stack clatters like one. The clip comes from the [`ImpactSoundBank`] entry
+//! for the two surfaces that hit.
+//!
+//! Playing hits are [`ImpactVoice`]s, at most [`ImpactSoundSettings::max_voices`] of them at once.
+//! When all are busy a harder hit takes over the voice of the weakest one still ringing, and every
+//! voice despawns once its clip finishes.
+
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_xpbd_3d::prelude::*;
+
+use crate::block::Block;
+use crate::hand_physics::HandCollider;
+use crate::sound_bank::{ImpactSoundBank, Surface};
+use crate::Floor;
+
+pub struct ImpactSoundPlugin;
+
+impl Plugin for ImpactSoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ImpactSoundSettings>();
+        app.init_resource::<ImpactCooldowns>();
+        app.init_resource::<ImpactSoundBank>();
+        app.add_systems(
+            PostUpdate,
+            play_impact_sounds.after(PhysicsSet::StepSimulation),
+        );
+    }
+}
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct ImpactSoundSettings {
+    /// Relative normal speed in meters per second below which contacts are silent.
+    pub min_speed: f32,
+    /// Volume per meter per second of impact speed.
+    pub volume_per_speed: f32,
+    pub max_volume: f32,
+    /// Seconds before the same pair of colliders can make a sound again.
+    pub pair_cooldown: f32,
+    /// Most impact sounds playing at the same time.
+    pub max_voices: usize,
+    /// Most impact sounds started in one frame, the hardest hits go first.
+    pub max_starts_per_frame: usize,
+    /// Seconds over which a playing voice's priority fades to nothing, so old voices get replaced
+    /// before fresh ones.
+    pub voice_fade: f32,
+}
+
+impl Default for ImpactSoundSettings {
+    fn default() -> Self {
+        Self {
+            min_speed: 0.15,
+            volume_per_speed: 1.0,
+            max_volume: 2.0,
+            pair_cooldown: 0.08,
+            max_voices: 16,
+            max_starts_per_frame: 4,
+            voice_fade: 0.5,
+        }
+    }
+}
+
+/// A playing impact sound.
+#[derive(Component, Copy, Clone, Debug)]
+pub struct ImpactVoice {
+    /// Kinetic energy in joules lost along the contact normal in the hit.
+    pub energy: f32,
+    /// When the sound started, in seconds since startup.
+    pub started: f32,
+}
+
+impl ImpactVoice {
+    fn priority(&self, now: f32, fade: f32) -> f32 {
+        let remaining = 1.0 - (now - self.started) / fade.max(f32::EPSILON);
+        self.energy * remaining.max(0.0)
+    }
+}
+
+/// A hit waiting for a voice.
+struct Impact {
+    point: Vec3,
+    energy: f32,
+    source: Handle<AudioSource>,
+    volume: f32,
+    speed: f32,
+    pair: (Entity, Entity),
+}
+
+/// When each pair of colliders last made a sound.
+#[derive(Resource, Default, Debug)]
+struct ImpactCooldowns(HashMap<(Entity, Entity), f32>);
+
+/// What `entity` is made of, anything that isn't a block or a hand counts as floor.
+fn surface(
+    entity: Entity,
+    surfaces: &Query<(Option<&Block>, Has<Floor>, Has<HandCollider>)>,
+) -> Surface {
+    match surfaces.get(entity) {
+        Ok((Some(block), _, _)) => Surface::Block(block.material),
+        Ok((None, false, true)) => Surface::Hand,
+        _ => Surface::Floor,
+    }
+}
+
+/// The velocity the body had before the contact was solved, in world space at `point`.
+fn point_velocity(
+    point: Vec3,
+    body: Option<(
+        &Position,
+        &Rotation,
+        &CenterOfMass,
+        &PreSolveLinearVelocity,
+        &PreSolveAngularVelocity,
+    )>,
+) -> Vec3 {
+    let Some((position, rotation, center_of_mass, linear, angular)) = body else {
+        return Vec3::ZERO;
+    };
+    let center = position.0 + rotation.0 * center_of_mass.0;
+    linear.0 + angular.0.cross(point - center)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn play_impact_sounds(
+    mut commands: Commands,
+    mut collisions: EventReader<Collision>,
+    mut cooldowns: ResMut<ImpactCooldowns>,
+    settings: Res<ImpactSoundSettings>,
+    time: Res<Time>,
+    bank: Res<ImpactSoundBank>,
+    colliders: Query<(&Position, &Rotation)>,
+    surfaces: Query<(Option<&Block>, Has<Floor>, Has<HandCollider>)>,
+    bodies: Query<(
+        &Position,
+        &Rotation,
+        &CenterOfMass,
+        &PreSolveLinearVelocity,
+        &PreSolveAngularVelocity,
+    )>,
+    inverse_masses: Query<&InverseMass>,
+    sensors: Query<(), With<Sensor>>,
+    voices: Query<(Entity, &ImpactVoice)>,
+) {
+    let now = time.elapsed_seconds();
+    cooldowns
+        .0
+        .retain(|_, last| now - *last < settings.pair_cooldown);
+
+    let mut impacts = Vec::new();
+    for Collision(contacts) in collisions.read() {
+        let pair = (
+            contacts.entity1.min(contacts.entity2),
+            contacts.entity1.max(contacts.entity2),
+        );
+        let new_contact = contacts.during_current_frame && !contacts.during_previous_frame;
+        if !new_contact || cooldowns.0.contains_key(&pair) {
+            continue;
+        }
+        if sensors.contains(contacts.entity1) || sensors.contains(contacts.entity2) {
+            continue;
+        }
+        let Ok((position1, rotation1)) = colliders.get(contacts.entity1) else {
+            continue;
+        };
+        let body_entity1 = contacts.body_entity1.unwrap_or(contacts.entity1);
+        let body_entity2 = contacts.body_entity2.unwrap_or(contacts.entity2);
+        let body1 = bodies.get(body_entity1).ok();
+        let body2 = bodies.get(body_entity2).ok();
+
+        // The hardest hitting contact point of the pair
+        let impact = contacts
+            .manifolds
+            .iter()
+            .flat_map(|manifold| &manifold.contacts)
+            .map(|contact| {
+                let point = position1.0 + rotation1.0 * contact.point1;
+                let normal = rotation1.0 * contact.normal1;
+                let relative = point_velocity(point, body1) - point_velocity(point, body2);
+                (point, relative.dot(normal).abs())
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        let Some((point, speed)) = impact else {
+            continue;
+        };
+        if speed < settings.min_speed {
+            continue;
+        }
+
+        let Some((clip, clip_speed)) = bank.pick(
+            surface(body_entity1, &surfaces),
+            surface(body_entity2, &surfaces),
+        ) else {
+            continue;
+        };
+
+        // Static bodies have no inverse mass, the hit is as hard as the moving body makes it
+        let inverse_mass = |entity| inverse_masses.get(entity).map_or(0.0, |inverse| inverse.0);
+        let inverse_mass = inverse_mass(body_entity1) + inverse_mass(body_entity2);
+        if inverse_mass <= 0.0 {
+            continue;
+        }
+        impacts.push(Impact {
+            point,
+            energy: 0.5 * speed * speed / inverse_mass,
+            source: clip.source.clone(),
+            volume: (speed * settings.volume_per_speed * clip.volume).min(settings.max_volume),
+            speed: clip_speed,
+            pair,
+        });
+    }
+
+    impacts.sort_by(|a, b| b.energy.total_cmp(&a.energy));
+    let mut playing: Vec<_> = voices
+        .iter()
+        .map(|(entity, voice)| (entity, voice.priority(now, settings.voice_fade)))
+        .collect();
+    for impact in impacts.into_iter().take(settings.max_starts_per_frame) {
+        if playing.len() >= settings.max_voices {
+            let weakest = playing
+                .iter()
+                .enumerate()
+                .min_by(|(_, (_, a)), (_, (_, b))| a.total_cmp(b))
+                .filter(|(_, (_, priority))| *priority < impact.energy);
+            // The rest of the hits are weaker still
+            let Some((i, _)) = weakest else { break };
+            let (entity, _) = playing.swap_remove(i);
+            commands.entity(entity).despawn();
+        }
+
+        cooldowns.0.insert(impact.pair, now);
+        let voice = commands
+            .spawn((
+                AudioBundle {
+                    source: impact.source,
+                    settings: PlaybackSettings {
+                        mode: PlaybackMode::Despawn,
+                        volume: Volume::new(impact.volume),
+                        speed: impact.speed,
+                        paused: false,
+                        spatial: true,
+                        spatial_scale: None,
+                    },
+                },
+                SpatialBundle::from_transform(Transform::from_translation(impact.point)),
+                ImpactVoice {
+                    energy: impact.energy,
+                    started: now,
+                },
+            ))
+            .id();
+        playing.push((voice, impact.energy));
+    }
+}
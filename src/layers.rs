@@ -0,0 +1,33 @@
+//! Which things collide with which.
+//!
+//! Hands push blocks but not each other, a held block ignores the hands so it doesn't fight the
+//! one holding it, and UI panels are left alone by everything. Widgets are poked by the hand
+//! tracking directly, their colliders are only there to be found by spatial queries.
+
+use bevy_xpbd_3d::prelude::*;
+
+#[derive(PhysicsLayer, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Hands,
+    Blocks,
+    Floor,
+    /// Blocks held by a hand.
+    Held,
+    /// Widgets and panels.
+    Ui,
+}
+
+impl Layer {
+    /// Collision layers of something on this layer, with the layers it collides with.
+    pub fn collision_layers(self) -> CollisionLayers {
+        let filters: &[Layer] = match self {
+            Layer::Hands => &[Layer::Blocks],
+            Layer::Blocks => &[Layer::Hands, Layer::Blocks, Layer::Floor, Layer::Held],
+            Layer::Floor => &[Layer::Blocks, Layer::Held],
+            Layer::Held => &[Layer::Blocks, Layer::Floor],
+            Layer::Ui => &[],
+        };
+        let filters = filters.iter().fold(0, |bits, layer| bits | layer.to_bits());
+        CollisionLayers::new(self, LayerMask(filters))
+    }
+}
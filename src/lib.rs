@@ -1,21 +1,59 @@
 //! A simple 3D scene with light shining over a cube sitting on a plane
 
+use crate::controllers::OpenXrControllerPlugin;
 use crate::cube_creation::CubeCreationPlugin;
+use crate::deletion::DeletionPlugin;
+use crate::dimensions::DimensionReadoutPlugin;
+use crate::grab::GrabPlugin;
+use crate::hand_menu::HandMenuPlugin;
+use crate::hand_physics::HandPhysicsPlugin;
+use crate::history::HistoryPlugin;
+use crate::impact_sounds::ImpactSoundPlugin;
+use crate::layers::Layer;
+use crate::palette::PalettePlugin;
+use crate::persistence::BlockPersistencePlugin;
 use bevy::asset::AssetLoader;
-use bevy::audio::{PlaybackMode, Volume};
 use bevy::prelude::*;
 use bevy::render::render_resource::AsBindGroup;
 use bevy_embedded_assets::EmbeddedAssetPlugin;
 use bevy_openxr::resources::OxrSession;
+#[cfg(not(feature = "desktop"))]
 use bevy_openxr::{add_xr_plugins, init::OxrInitPlugin, types::OxrExtensions};
 use bevy_xpbd_3d::prelude::*;
-use bevy_xr::hands::HandBoneRadius;
 
+pub mod block;
+pub mod block_material;
+pub mod controllers;
 pub mod cube_creation;
+pub mod deletion;
+#[cfg(feature = "desktop")]
+pub mod desktop;
+pub mod dimensions;
+pub mod gizmo_text;
+pub mod grab;
+pub mod hand_menu;
+pub mod hand_physics;
+pub mod hand_recording;
+pub mod hands;
+pub mod history;
+pub mod impact_sounds;
+pub mod layers;
+pub mod palette;
+pub mod persistence;
+pub mod pinch;
+pub mod shapes;
+pub mod snapping;
+pub mod sound_bank;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+pub mod widgets;
 
 #[bevy_main]
 pub fn main() {
     let mut app = App::new();
+    #[cfg(feature = "desktop")]
+    app.add_plugins((DefaultPlugins, desktop::DesktopPlugin));
+    #[cfg(not(feature = "desktop"))]
     app.add_plugins(add_xr_plugins(DefaultPlugins).set(OxrInitPlugin {
         app_info: default(),
         exts: {
@@ -31,10 +69,25 @@ pub fn main() {
         resolutions: default(),
         synchronous_pipeline_compilation: default(),
     }))
+    // Clear to transparent so passthrough shows behind the scene
+    .insert_resource(ClearColor(Color::NONE))
+    .add_plugins(OpenXrControllerPlugin);
+
     // System for requesting refresh rate ( should refactor and upstream into bevy_openxr )
-    //.add_systems(Update, set_requested_refresh_rate)
+    //app.add_systems(Update, set_requested_refresh_rate);
     // Our plugins
-    .add_plugins((CubeCreationPlugin, CustomPhysicsIntegrations))
+    app.add_plugins((
+        CubeCreationPlugin,
+        GrabPlugin,
+        DeletionPlugin,
+        BlockPersistencePlugin,
+        HistoryPlugin,
+        ImpactSoundPlugin,
+        HandPhysicsPlugin,
+        DimensionReadoutPlugin,
+        PalettePlugin,
+        HandMenuPlugin,
+    ))
     // Third party plugins
     .add_plugins((
         EmbeddedAssetPlugin::default(),
@@ -49,10 +102,13 @@ pub fn main() {
         brightness: 500.0,
     })
     .insert_resource(Msaa::Off)
-    .insert_resource(ClearColor(Color::NONE))
     .run();
 }
 
+/// The ground blocks are built on.
+#[derive(Component, Copy, Clone, Debug)]
+pub struct Floor;
+
 /// set up a simple 3D scene
 fn setup(
     mut commands: Commands,
@@ -62,8 +118,10 @@ fn setup(
     // Spawns a plane
     // For improved performance set the `unlit` value to true on standard materials
     commands.spawn((
+        Floor,
         RigidBody::Static,
         Collider::cuboid(1.0, 0.002, 1.0),
+        Layer::Floor.collision_layers(),
         PbrBundle {
             mesh: meshes.add(Plane3d::default().mesh().size(1.0, 1.0)),
             material: materials.add(Color::rgb(0.3, 0.5, 0.3)),
@@ -73,58 +131,6 @@ fn setup(
     ));
 }
 
-pub struct CustomPhysicsIntegrations;
-
-impl Plugin for CustomPhysicsIntegrations {
-    fn build(&self, app: &mut App) {
-        app.add_systems(Update, hand_collider);
-        app.add_systems(Update, play_sound_when_colliding);
-    }
-}
-
-fn hand_collider(
-    query: Query<(Entity, &HandBoneRadius), Without<Collider>>,
-    mut commands: Commands,
-) {
-    for (entity, radius) in &query {
-        commands
-            .entity(entity)
-            .insert((Collider::sphere(radius.0), RigidBody::Static));
-    }
-}
-
-fn play_sound_when_colliding(
-    query: Query<(&CollidingEntities)>,
-    asset_server: Res<AssetServer>,
-    mut commands: Commands,
-    transforms: Query<&GlobalTransform>,
-    velocities: Query<&LinearVelocity>,
-) {
-    for (colliding_entities) in &query {
-        for e in colliding_entities.0.iter() {
-            if let Ok(t) = transforms.get(*e) {
-                if velocities.get(*e).is_ok_and(|a| a.length() >= 1.0) {
-                    commands.spawn((
-                        AudioBundle {
-                            source: asset_server.load("embedded://plastic-hit.ogg"),
-                            settings: PlaybackSettings {
-                                mode: PlaybackMode::Once,
-                                volume: Volume::new(velocities.get(*e).unwrap().length() * 2.0),
-                                speed: 1.0,
-                                paused: false,
-                                spatial: true,
-                                spatial_scale: None,
-                            },
-                        },
-                        SpatialBundle::from_transform(t.compute_transform()),
-                    ));
-                    return;
-                }
-            }
-        }
-    }
-}
-
 fn set_requested_refresh_rate(mut local: Local<bool>, mut session: Option<ResMut<OxrSession>>) {
     if session.is_none() {
         return;
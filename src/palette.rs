@@ -0,0 +1,573 @@
+//! Picking the color and texture of new blocks, and repainting existing ones.
+//!
+//! A floating palette holds an HSV color wheel with a slider for its value, a row of grays, a row
+//! of textures and a "random" swatch. Touching a swatch with an index tip selects it for new blocks
+//! in [`SelectedPaint`] and dips that finger in the paint for a while, touching a block with it
+//! then repaints the block and sends a [`BlockRepainted`] event.
+
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::utils::HashSet;
+use bevy_xpbd_3d::prelude::*;
+use bevy_xr::hands::HandBone;
+use random_number::random;
+use serde::{Deserialize, Serialize};
+
+use crate::block::{Block, BlockId, BlockRepainted};
+use crate::block_material::BlockMaterial;
+use crate::gizmo_text::{draw_text, TextAnchor};
+use crate::hands::{Hand, HandsPlugin, HandsSet, HandsState};
+use crate::widgets::{Slider, SliderChanged, Widget, WidgetPlugin, WidgetSet};
+
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<HandsPlugin>() {
+            app.add_plugins(HandsPlugin);
+        }
+        if !app.is_plugin_added::<WidgetPlugin>() {
+            app.add_plugins(WidgetPlugin);
+        }
+        app.init_resource::<PaletteSettings>();
+        app.init_resource::<SelectedPaint>();
+        app.init_resource::<PaletteTextures>();
+        app.init_resource::<PaintBrushes>();
+        app.add_event::<BlockRepainted>();
+        app.add_systems(Startup, spawn_palette);
+        app.add_systems(
+            Update,
+            (
+                set_wheel_value,
+                pick_swatches,
+                paint_blocks,
+                draw_palette_selection,
+            )
+                .chain()
+                .after(HandsSet)
+                .after(WidgetSet),
+        );
+    }
+}
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct PaletteSettings {
+    /// Where the palette floats, its swatches face along its +Z.
+    pub transform: Transform,
+    /// Radius of a swatch in meters.
+    pub swatch_radius: f32,
+    /// How close in meters an index tip has to get to a swatch or block to touch it.
+    pub touch_distance: f32,
+    /// Seconds a finger stays dipped in paint after touching a swatch.
+    pub brush_duration: f32,
+    /// Whether touching blocks with a dipped finger repaints them.
+    pub repaint: bool,
+}
+
+impl Default for PaletteSettings {
+    fn default() -> Self {
+        Self {
+            transform: Transform::from_xyz(-0.55, 1.25, 0.0)
+                .with_rotation(Quat::from_rotation_y(0.6) * Quat::from_rotation_x(-0.4)),
+            swatch_radius: 0.008,
+            touch_distance: 0.01,
+            brush_duration: 8.0,
+            repaint: true,
+        }
+    }
+}
+
+/// A pattern multiplied over a block's color.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlockTexture {
+    #[default]
+    Plain,
+    Checker,
+    Stripes,
+    Dots,
+}
+
+impl BlockTexture {
+    pub const ALL: [BlockTexture; 4] = [
+        BlockTexture::Plain,
+        BlockTexture::Checker,
+        BlockTexture::Stripes,
+        BlockTexture::Dots,
+    ];
+
+    /// Brightness of the pattern at pixel `x`, `y` of a [`TEXTURE_SIZE`] square image.
+    fn brightness(self, x: u32, y: u32) -> f32 {
+        const CELL: u32 = TEXTURE_SIZE / 8;
+        match self {
+            BlockTexture::Plain => 1.0,
+            BlockTexture::Checker => {
+                if (x / CELL + y / CELL) % 2 == 0 {
+                    1.0
+                } else {
+                    0.6
+                }
+            }
+            BlockTexture::Stripes => {
+                if (x + y) / CELL % 2 == 0 {
+                    1.0
+                } else {
+                    0.6
+                }
+            }
+            BlockTexture::Dots => {
+                let center = CELL as f32 / 2.0;
+                let dx = (x % CELL) as f32 + 0.5 - center;
+                let dy = (y % CELL) as f32 + 0.5 - center;
+                if dx * dx + dy * dy < center * center * 0.4 {
+                    0.5
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+}
+
+/// Width and height in pixels of the generated textures.
+const TEXTURE_SIZE: u32 = 64;
+
+/// The images of every [`BlockTexture`].
+#[derive(Resource, Clone, Debug)]
+pub struct PaletteTextures(Vec<(BlockTexture, Handle<Image>)>);
+
+impl FromWorld for PaletteTextures {
+    fn from_world(world: &mut World) -> Self {
+        let mut images = world.resource_mut::<Assets<Image>>();
+        let textures = BlockTexture::ALL
+            .into_iter()
+            .filter(|texture| *texture != BlockTexture::Plain)
+            .map(|texture| {
+                let mut data = Vec::with_capacity((TEXTURE_SIZE * TEXTURE_SIZE * 4) as usize);
+                for y in 0..TEXTURE_SIZE {
+                    for x in 0..TEXTURE_SIZE {
+                        let value = (texture.brightness(x, y) * 255.0) as u8;
+                        data.extend([value, value, value, 255]);
+                    }
+                }
+                let image = Image::new(
+                    Extent3d {
+                        width: TEXTURE_SIZE,
+                        height: TEXTURE_SIZE,
+                        depth_or_array_layers: 1,
+                    },
+                    TextureDimension::D2,
+                    data,
+                    TextureFormat::Rgba8UnormSrgb,
+                    RenderAssetUsages::default(),
+                );
+                (texture, images.add(image))
+            })
+            .collect();
+        Self(textures)
+    }
+}
+
+impl PaletteTextures {
+    pub fn image(&self, texture: BlockTexture) -> Option<Handle<Image>> {
+        self.0
+            .iter()
+            .find(|(t, _)| *t == texture)
+            .map(|(_, image)| image.clone())
+    }
+
+    /// Which texture `image` is, [`BlockTexture::Plain`] if it isn't one of ours.
+    pub fn texture_of(&self, image: Option<&Handle<Image>>) -> BlockTexture {
+        image
+            .and_then(|image| self.0.iter().find(|(_, i)| i == image))
+            .map_or(BlockTexture::Plain, |(texture, _)| *texture)
+    }
+
+    /// The look of a `material` block painted in `color` with `texture`.
+    pub fn standard_material(
+        &self,
+        material: BlockMaterial,
+        color: Color,
+        texture: BlockTexture,
+    ) -> StandardMaterial {
+        StandardMaterial {
+            base_color_texture: self.image(texture),
+            ..material.standard_material(color)
+        }
+    }
+}
+
+/// What new blocks are painted with.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Paint {
+    Color(Color),
+    /// A different random color for every block.
+    Random,
+}
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct SelectedPaint {
+    pub paint: Paint,
+    pub texture: BlockTexture,
+    /// Value of the colors on the wheel, from 0 for black to 1 for the brightest.
+    pub value: f32,
+}
+
+impl Default for SelectedPaint {
+    fn default() -> Self {
+        Self {
+            paint: Paint::Random,
+            texture: BlockTexture::Plain,
+            value: 1.0,
+        }
+    }
+}
+
+impl Paint {
+    /// The color to paint the next block with.
+    pub fn color(self) -> Color {
+        match self {
+            Paint::Color(color) => color,
+            Paint::Random => Color::rgb_u8(random!(0..255), random!(0..255), random!(0..255)),
+        }
+    }
+}
+
+/// Converts hue in degrees, saturation and value to a color.
+pub fn hsv(hue: f32, saturation: f32, value: f32) -> Color {
+    // Value and saturation map onto lightness and saturation of HSL
+    let lightness = value * (1.0 - saturation / 2.0);
+    let saturation = if lightness <= 0.0 || lightness >= 1.0 {
+        0.0
+    } else {
+        (value - lightness) / lightness.min(1.0 - lightness)
+    };
+    Color::hsl(hue, saturation, lightness)
+}
+
+/// What touching a swatch picks.
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub enum Swatch {
+    Color(Color),
+    /// A color on the wheel, at the value picked with the slider.
+    Wheel {
+        hue: f32,
+        saturation: f32,
+    },
+    Texture(BlockTexture),
+    Random,
+}
+
+/// The floating palette the swatches are children of.
+#[derive(Component, Copy, Clone, Debug)]
+pub struct Palette;
+
+/// The slider on the palette setting [`SelectedPaint::value`].
+#[derive(Component, Copy, Clone, Debug)]
+pub struct ValueSlider;
+
+/// Paint on a fingertip, ready to repaint blocks.
+#[derive(Copy, Clone, Debug)]
+pub struct Brush {
+    pub paint: Paint,
+    pub texture: BlockTexture,
+    /// Seconds since startup at which the paint dries off.
+    pub until: f32,
+}
+
+/// The brush on each index tip, left then right.
+#[derive(Resource, Copy, Clone, Debug, Default)]
+pub struct PaintBrushes(pub [Option<Brush>; 2]);
+
+/// Local positions of every swatch on the palette.
+fn swatch_layout() -> Vec<(Swatch, Vec3)> {
+    let center = Swatch::Wheel {
+        hue: 0.0,
+        saturation: 0.0,
+    };
+    let mut swatches = vec![(center, Vec3::ZERO)];
+    // The color wheel, hue around and saturation outwards
+    for ring in 1..=3 {
+        let saturation = ring as f32 / 3.0;
+        for step in 0..12 {
+            let hue = step as f32 * 30.0;
+            let angle = hue.to_radians();
+            let position = Vec3::new(angle.cos(), angle.sin(), 0.0) * 0.02 * ring as f32;
+            swatches.push((Swatch::Wheel { hue, saturation }, position));
+        }
+    }
+    for (i, value) in [0.0, 0.25, 0.5, 0.75].into_iter().enumerate() {
+        let position = Vec3::new(-0.045 + i as f32 * 0.03, -0.085, 0.0);
+        swatches.push((Swatch::Color(Color::rgb(value, value, value)), position));
+    }
+    for (i, texture) in BlockTexture::ALL.into_iter().enumerate() {
+        let position = Vec3::new(-0.045 + i as f32 * 0.03, -0.11, 0.0);
+        swatches.push((Swatch::Texture(texture), position));
+    }
+    swatches.push((Swatch::Random, Vec3::new(0.075, 0.075, 0.0)));
+    swatches
+}
+
+fn spawn_palette(
+    mut commands: Commands,
+    settings: Res<PaletteSettings>,
+    selected: Res<SelectedPaint>,
+    textures: Res<PaletteTextures>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let mesh = meshes.add(Sphere::new(settings.swatch_radius).mesh().uv(16, 8));
+    commands
+        .spawn((Palette, SpatialBundle::from_transform(settings.transform)))
+        .with_children(|palette| {
+            for (swatch, position) in swatch_layout() {
+                let material = match swatch {
+                    Swatch::Color(color) => StandardMaterial::from(color),
+                    Swatch::Wheel { hue, saturation } => {
+                        StandardMaterial::from(hsv(hue, saturation, selected.value))
+                    }
+                    Swatch::Texture(texture) => {
+                        textures.standard_material(BlockMaterial::Wood, Color::WHITE, texture)
+                    }
+                    // Stands out from the wheel
+                    Swatch::Random => StandardMaterial {
+                        base_color: Color::WHITE,
+                        base_color_texture: textures.image(BlockTexture::Dots),
+                        metallic: 1.0,
+                        perceptual_roughness: 0.2,
+                        ..default()
+                    },
+                };
+                palette.spawn((
+                    swatch,
+                    PbrBundle {
+                        mesh: mesh.clone(),
+                        material: materials.add(material),
+                        transform: Transform::from_translation(position),
+                        ..default()
+                    },
+                ));
+            }
+            palette.spawn((
+                ValueSlider,
+                Widget::new(Vec2::new(0.12, 0.015), "value"),
+                Slider {
+                    value: selected.value,
+                    min: 0.0,
+                    max: 1.0,
+                    step: 0.05,
+                },
+                SpatialBundle::from_transform(Transform::from_xyz(0.0, -0.14, 0.0)),
+            ));
+        });
+}
+
+/// Recolors the wheel when its value slider moves, the selected color moves along with it.
+fn set_wheel_value(
+    mut slid: EventReader<SliderChanged>,
+    mut selected: ResMut<SelectedPaint>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    sliders: Query<(), With<ValueSlider>>,
+    swatches: Query<(&Swatch, &Handle<StandardMaterial>)>,
+) {
+    let Some(value) = slid
+        .read()
+        .filter(|event| sliders.contains(event.entity))
+        .last()
+        .map(|event| event.value)
+    else {
+        return;
+    };
+    let previous = *selected;
+    selected.value = value;
+    for (swatch, material) in &swatches {
+        let Swatch::Wheel { hue, saturation } = *swatch else {
+            continue;
+        };
+        if previous.paint == Paint::Color(hsv(hue, saturation, previous.value)) {
+            selected.paint = Paint::Color(hsv(hue, saturation, value));
+        }
+        if let Some(material) = materials.get_mut(material) {
+            material.base_color = hsv(hue, saturation, value);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn pick_swatches(
+    time: Res<Time>,
+    settings: Res<PaletteSettings>,
+    mut selected: ResMut<SelectedPaint>,
+    mut brushes: ResMut<PaintBrushes>,
+    hands: Res<HandsState>,
+    swatches: Query<(Entity, &Swatch, &GlobalTransform)>,
+    mut touching: Local<[Option<Entity>; 2]>,
+    mut pinching: Local<[bool; 2]>,
+) {
+    let now = time.elapsed_seconds();
+    // Pinching means the hand is doing something else, wipe the paint off
+    for (i, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let pinched = hands.hand(hand).pinching;
+        if pinched && !pinching[i] {
+            brushes.0[i] = None;
+        }
+        pinching[i] = pinched;
+    }
+    for brush in &mut brushes.0 {
+        if brush.is_some_and(|brush| brush.until < now) {
+            *brush = None;
+        }
+    }
+
+    for (i, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let Some(tip) = hands.hand(hand).joint(HandBone::IndexTip) else {
+            continue;
+        };
+        let tip = tip.translation;
+        let touched = swatches
+            .iter()
+            .filter(|(_, _, transform)| {
+                transform.translation().distance(tip)
+                    < settings.swatch_radius + settings.touch_distance
+            })
+            .min_by(|(_, _, a), (_, _, b)| {
+                a.translation()
+                    .distance(tip)
+                    .total_cmp(&b.translation().distance(tip))
+            });
+
+        let entity = touched.map(|(entity, _, _)| entity);
+        if entity == touching[i] {
+            continue;
+        }
+        touching[i] = entity;
+        let Some((_, swatch, _)) = touched else {
+            continue;
+        };
+        match *swatch {
+            Swatch::Color(color) => selected.paint = Paint::Color(color),
+            Swatch::Wheel { hue, saturation } => {
+                selected.paint = Paint::Color(hsv(hue, saturation, selected.value));
+            }
+            Swatch::Texture(texture) => selected.texture = texture,
+            Swatch::Random => selected.paint = Paint::Random,
+        }
+        brushes.0[i] = Some(Brush {
+            paint: selected.paint,
+            texture: selected.texture,
+            until: now + settings.brush_duration,
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn paint_blocks(
+    settings: Res<PaletteSettings>,
+    brushes: Res<PaintBrushes>,
+    textures: Res<PaletteTextures>,
+    spatial_query: SpatialQuery,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    hands: Res<HandsState>,
+    mut blocks: Query<(&BlockId, &Block, &mut Handle<StandardMaterial>)>,
+    mut repainted: EventWriter<BlockRepainted>,
+    mut touching: Local<[HashSet<Entity>; 2]>,
+) {
+    for (i, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let Some(brush) = brushes.0[i].filter(|_| settings.repaint) else {
+            touching[i].clear();
+            continue;
+        };
+        let Some(tip) = hands.hand(hand).joint(HandBone::IndexTip) else {
+            continue;
+        };
+        let tip = tip.translation;
+
+        let touched: HashSet<Entity> = spatial_query
+            .shape_intersections(
+                &Collider::sphere(settings.touch_distance),
+                tip,
+                Quat::IDENTITY,
+                SpatialQueryFilter::default(),
+            )
+            .into_iter()
+            .filter(|entity| blocks.contains(*entity))
+            .collect();
+        // Only paint blocks when the finger first touches them
+        for entity in touched.difference(&touching[i]) {
+            let Ok((id, block, mut material)) = blocks.get_mut(*entity) else {
+                continue;
+            };
+            let paint = materials.add(textures.standard_material(
+                block.material,
+                brush.paint.color(),
+                brush.texture,
+            ));
+            repainted.send(BlockRepainted {
+                id: *id,
+                from: std::mem::replace(&mut *material, paint.clone()),
+                to: paint,
+            });
+        }
+        touching[i] = touched;
+    }
+}
+
+fn draw_palette_selection(
+    mut gizmos: Gizmos,
+    settings: Res<PaletteSettings>,
+    selected: Res<SelectedPaint>,
+    brushes: Res<PaintBrushes>,
+    hands: Res<HandsState>,
+    swatches: Query<(&Swatch, &GlobalTransform)>,
+    palettes: Query<&GlobalTransform, With<Palette>>,
+) {
+    for (swatch, transform) in &swatches {
+        let is_selected = match *swatch {
+            Swatch::Color(color) => selected.paint == Paint::Color(color),
+            Swatch::Wheel { hue, saturation } => {
+                selected.paint == Paint::Color(hsv(hue, saturation, selected.value))
+            }
+            Swatch::Texture(texture) => selected.texture == texture,
+            Swatch::Random => selected.paint == Paint::Random,
+        };
+        if !is_selected {
+            continue;
+        }
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        let Ok(normal) = Direction3d::new(rotation * Vec3::Z) else {
+            continue;
+        };
+        gizmos.circle(
+            translation,
+            normal,
+            settings.swatch_radius * 1.5,
+            Color::WHITE,
+        );
+    }
+
+    for palette in &palettes {
+        let label =
+            palette.compute_transform() * Transform::from_translation(Vec3::new(0.075, 0.09, 0.0));
+        draw_text(
+            &mut gizmos,
+            "random",
+            label,
+            0.006,
+            TextAnchor::Center,
+            Color::WHITE,
+        );
+    }
+
+    // Show the paint on dipped fingertips
+    for (i, hand) in [Hand::Left, Hand::Right].into_iter().enumerate() {
+        let Some(brush) = brushes.0[i] else { continue };
+        let color = match brush.paint {
+            Paint::Color(color) => color,
+            Paint::Random => Color::WHITE,
+        };
+        let Some(tip) = hands.hand(hand).joint(HandBone::IndexTip) else {
+            continue;
+        };
+        gizmos.sphere(tip.translation, Quat::IDENTITY, 0.012, color);
+    }
+}
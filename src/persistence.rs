@@ -0,0 +1,246 @@
+//! Saving and loading the blocks in the scene.
+//!
+//! Blocks are stored as a RON [`BlockScene`]. [`SaveBlocks`] and [`LoadBlocks`] events save to and
+//! load from [`BlockPersistenceSettings::path`], the scene is saved automatically when the app is
+//! suspended or closed and loaded again on startup, so builds carry over between sessions.
+
+use std::path::PathBuf;
+use std::{fs, io};
+
+use bevy::app::AppExit;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::ApplicationLifetime;
+use bevy_xpbd_3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::block::{spawn_block, Block};
+use crate::block_material::BlockMaterial;
+use crate::history::BlockHistory;
+use crate::palette::{BlockTexture, PaletteTextures};
+use crate::shapes::ShapeKind;
+
+pub struct BlockPersistencePlugin;
+
+impl Plugin for BlockPersistencePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BlockPersistenceSettings>();
+        app.init_resource::<PaletteTextures>();
+        app.add_event::<SaveBlocks>();
+        app.add_event::<LoadBlocks>();
+        app.add_event::<ApplicationLifetime>();
+        app.add_systems(Startup, load_on_startup);
+        app.add_systems(PostUpdate, (save_blocks, load_blocks).chain());
+        // The runner quits right after the frame an exit is requested in, so save in the same
+        // frame, after anything that could have asked to exit
+        app.add_systems(Last, autosave);
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct BlockPersistenceSettings {
+    /// File the scene is saved to and loaded from.
+    pub path: PathBuf,
+    /// Save when the app is suspended or closed.
+    pub autosave: bool,
+    /// Load the saved scene when the app starts.
+    pub load_on_startup: bool,
+}
+
+impl Default for BlockPersistenceSettings {
+    fn default() -> Self {
+        // The working directory isn't writable on Android, use the app's internal storage there
+        #[cfg(target_os = "android")]
+        let directory = bevy::winit::ANDROID_APP
+            .get()
+            .and_then(|app| app.internal_data_path())
+            .unwrap_or_default();
+        #[cfg(not(target_os = "android"))]
+        let directory = PathBuf::new();
+
+        Self {
+            path: directory.join("blocks.ron"),
+            autosave: true,
+            load_on_startup: true,
+        }
+    }
+}
+
+/// Saves every block to [`BlockPersistenceSettings::path`].
+#[derive(Event, Copy, Clone, Debug, Default)]
+pub struct SaveBlocks;
+
+/// Replaces every block with the ones saved at [`BlockPersistenceSettings::path`].
+#[derive(Event, Copy, Clone, Debug, Default)]
+pub struct LoadBlocks;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct BlockScene {
+    pub blocks: Vec<SavedBlock>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SavedBlock {
+    pub shape: ShapeKind,
+    pub size: [f32; 3],
+    /// Missing in scenes saved before blocks had materials.
+    #[serde(default)]
+    pub material: BlockMaterial,
+    /// Missing in scenes saved before blocks had textures.
+    #[serde(default)]
+    pub texture: BlockTexture,
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    /// sRGBA
+    pub color: [f32; 4],
+    pub linear_velocity: [f32; 3],
+    pub angular_velocity: [f32; 3],
+}
+
+impl BlockScene {
+    pub fn load(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let text = fs::read_to_string(path.into())?;
+        ron::from_str(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn save(&self, path: impl Into<PathBuf>) -> io::Result<()> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        fs::write(path.into(), text)
+    }
+}
+
+fn load_on_startup(settings: Res<BlockPersistenceSettings>, mut load: EventWriter<LoadBlocks>) {
+    if settings.load_on_startup && settings.path.exists() {
+        load.send(LoadBlocks);
+    }
+}
+
+fn autosave(
+    settings: Res<BlockPersistenceSettings>,
+    mut lifetime: EventReader<ApplicationLifetime>,
+    mut exit: EventReader<AppExit>,
+    saver: BlockSaver,
+) {
+    let suspended = lifetime
+        .read()
+        .any(|e| matches!(e, ApplicationLifetime::Suspended));
+    let exiting = exit.read().count() > 0;
+    if settings.autosave && (suspended || exiting) {
+        saver.save();
+    }
+}
+
+fn save_blocks(mut events: EventReader<SaveBlocks>, saver: BlockSaver) {
+    if events.read().count() > 0 {
+        saver.save();
+    }
+}
+
+/// Everything needed to write the blocks to [`BlockPersistenceSettings::path`].
+#[derive(SystemParam)]
+struct BlockSaver<'w, 's> {
+    settings: Res<'w, BlockPersistenceSettings>,
+    materials: Res<'w, Assets<StandardMaterial>>,
+    textures: Res<'w, PaletteTextures>,
+    blocks: Query<
+        'w,
+        's,
+        (
+            &'static Block,
+            &'static Transform,
+            &'static Handle<StandardMaterial>,
+            Option<&'static LinearVelocity>,
+            Option<&'static AngularVelocity>,
+        ),
+    >,
+}
+
+impl BlockSaver<'_, '_> {
+    fn save(&self) {
+        let scene = BlockScene {
+            blocks: self
+                .blocks
+                .iter()
+                .map(|(block, transform, material, linear, angular)| {
+                    let material = self.materials.get(material);
+                    SavedBlock {
+                        shape: block.shape,
+                        size: block.size.to_array(),
+                        material: block.material,
+                        texture: self.textures.texture_of(
+                            material.and_then(|material| material.base_color_texture.as_ref()),
+                        ),
+                        translation: transform.translation.to_array(),
+                        rotation: transform.rotation.to_array(),
+                        color: material
+                            .map_or(Color::WHITE, |material| material.base_color)
+                            .as_rgba_f32(),
+                        linear_velocity: linear.map_or(Vec3::ZERO, |v| v.0).to_array(),
+                        angular_velocity: angular.map_or(Vec3::ZERO, |v| v.0).to_array(),
+                    }
+                })
+                .collect(),
+        };
+        let path = &self.settings.path;
+        match scene.save(path) {
+            Ok(()) => info!("saved {} blocks to {path:?}", scene.blocks.len()),
+            Err(e) => error!("unable to save blocks to {path:?}: {e}"),
+        }
+    }
+}
+
+fn load_blocks(
+    mut commands: Commands,
+    mut events: EventReader<LoadBlocks>,
+    settings: Res<BlockPersistenceSettings>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    textures: Res<PaletteTextures>,
+    mut history: Option<ResMut<BlockHistory>>,
+    blocks: Query<Entity, With<Block>>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+
+    let scene = match BlockScene::load(&settings.path) {
+        Ok(scene) => scene,
+        Err(e) => {
+            error!("unable to load blocks from {:?}: {e}", settings.path);
+            return;
+        }
+    };
+    for entity in &blocks {
+        commands.entity(entity).despawn_recursive();
+    }
+    // The history refers to blocks that are gone now
+    if let Some(history) = history.as_mut() {
+        history.clear();
+    }
+    for saved in &scene.blocks {
+        let [r, g, b, a] = saved.color;
+        let material = materials.add(textures.standard_material(
+            saved.material,
+            Color::rgba(r, g, b, a),
+            saved.texture,
+        ));
+        let transform = Transform::from_translation(Vec3::from_array(saved.translation))
+            .with_rotation(Quat::from_array(saved.rotation));
+        let block = Block {
+            shape: saved.shape,
+            size: Vec3::from_array(saved.size),
+            material: saved.material,
+        };
+        let entity = spawn_block(&mut commands, &mut meshes, material, block, transform);
+        commands.entity(entity).insert((
+            LinearVelocity(Vec3::from_array(saved.linear_velocity)),
+            AngularVelocity(Vec3::from_array(saved.angular_velocity)),
+        ));
+    }
+    info!(
+        "loaded {} blocks from {:?}",
+        scene.blocks.len(),
+        settings.path
+    );
+}
@@ -0,0 +1,196 @@
+//! Per hand thumb to index pinch detection.
+//!
+//! Every index tip gets a [`PinchState`] that only changes phase once the thumb distance has been
+//! past its threshold for a minimum time, with separate engage and release thresholds, so tracking
+//! jitter doesn't toggle the pinch. Phase changes are reported once as [`PinchEvent`]s, which is
+//! what gestures starting and ending with a pinch, like grabbing, react to.
+
+use bevy::prelude::*;
+use bevy_xr::hands::{HandBone, LeftHand, RightHand};
+
+use crate::hands::Hand;
+
+pub struct PinchPlugin;
+
+impl Plugin for PinchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PinchSettings>();
+        app.add_event::<PinchEvent>();
+        app.add_systems(
+            Update,
+            (add_pinch_states, update_pinch_states)
+                .chain()
+                .in_set(PinchSet),
+        );
+    }
+}
+
+/// Updates every [`PinchState`], order systems reading them after this set.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PinchSet;
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct PinchSettings {
+    /// Thumb to index tip distance in meters below which a pinch starts engaging.
+    pub engage_distance: f32,
+    /// Thumb to index tip distance in meters above which a held pinch starts releasing.
+    pub release_distance: f32,
+    /// Seconds the thumb has to stay within `engage_distance` before the pinch is held.
+    pub engage_dwell: f32,
+    /// Seconds the thumb has to stay beyond `release_distance` before a held pinch is released.
+    pub release_dwell: f32,
+}
+
+impl Default for PinchSettings {
+    fn default() -> Self {
+        Self {
+            engage_distance: 0.04,
+            release_distance: 0.07,
+            engage_dwell: 0.05,
+            release_dwell: 0.1,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PinchPhase {
+    /// Thumb and index are apart.
+    Open,
+    /// Thumb and index are close, but not for long enough to count as a pinch yet.
+    Pinching,
+    /// Thumb and index have been close for at least the engage dwell time.
+    Held,
+    /// The pinch was let go this frame, becomes [`PinchPhase::Open`] on the next update.
+    Released,
+}
+
+/// Pinch state machine of one hand, lives on that hand's index tip entity.
+#[derive(Component, Copy, Clone, Debug)]
+pub struct PinchState {
+    pub hand: Hand,
+    phase: PinchPhase,
+    /// When the current phase was entered.
+    phase_since: f32,
+    /// When the thumb last moved beyond the release distance while held.
+    releasing_since: Option<f32>,
+}
+
+impl PinchState {
+    pub fn new(hand: Hand) -> Self {
+        Self {
+            hand,
+            phase: PinchPhase::Open,
+            phase_since: 0.0,
+            releasing_since: None,
+        }
+    }
+
+    pub fn phase(&self) -> PinchPhase {
+        self.phase
+    }
+
+    pub fn is_held(&self) -> bool {
+        self.phase == PinchPhase::Held
+    }
+
+    /// Advances the state machine with the current thumb to index distance, returning the event
+    /// to send if the pinch was just held or released.
+    pub fn update(
+        &mut self,
+        distance: f32,
+        now: f32,
+        settings: &PinchSettings,
+    ) -> Option<PinchEvent> {
+        match self.phase {
+            PinchPhase::Open | PinchPhase::Released => {
+                if distance <= settings.engage_distance {
+                    self.enter(PinchPhase::Pinching, now);
+                } else if self.phase == PinchPhase::Released {
+                    self.enter(PinchPhase::Open, now);
+                }
+                None
+            }
+            PinchPhase::Pinching => {
+                if distance >= settings.release_distance {
+                    self.enter(PinchPhase::Open, now);
+                    None
+                } else if now - self.phase_since >= settings.engage_dwell {
+                    self.enter(PinchPhase::Held, now);
+                    Some(PinchEvent::Held(self.hand))
+                } else {
+                    None
+                }
+            }
+            PinchPhase::Held => {
+                if distance < settings.release_distance {
+                    self.releasing_since = None;
+                    return None;
+                }
+                let releasing_since = *self.releasing_since.get_or_insert(now);
+                if now - releasing_since < settings.release_dwell {
+                    return None;
+                }
+                self.enter(PinchPhase::Released, now);
+                Some(PinchEvent::Released(self.hand))
+            }
+        }
+    }
+
+    fn enter(&mut self, phase: PinchPhase, now: f32) {
+        self.phase = phase;
+        self.phase_since = now;
+        self.releasing_since = None;
+    }
+}
+
+/// Sent once when a hand's pinch becomes held and once when it is released, by [`PinchState`] or
+/// by a [controller](crate::controllers) standing in for the hand.
+#[derive(Event, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PinchEvent {
+    Held(Hand),
+    Released(Hand),
+}
+
+#[allow(clippy::type_complexity)]
+fn add_pinch_states(
+    query: Query<(Entity, &HandBone, Has<LeftHand>, Has<RightHand>), Without<PinchState>>,
+    mut commands: Commands,
+) {
+    for (entity, bone, left, right) in &query {
+        if !matches!(bone, HandBone::IndexTip) {
+            continue;
+        }
+        let hand = match (left, right) {
+            (true, false) => Hand::Left,
+            (false, true) => Hand::Right,
+            _ => continue,
+        };
+        commands.entity(entity).insert(PinchState::new(hand));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_pinch_states(
+    time: Res<Time>,
+    settings: Res<PinchSettings>,
+    mut events: EventWriter<PinchEvent>,
+    mut pinches: Query<(&mut PinchState, &GlobalTransform)>,
+    left_hand: Query<(&HandBone, &GlobalTransform), (With<LeftHand>, Without<RightHand>)>,
+    right_hand: Query<(&HandBone, &GlobalTransform), (With<RightHand>, Without<LeftHand>)>,
+) {
+    let now = time.elapsed_seconds();
+    for (mut pinch, index_tip) in &mut pinches {
+        let is_thumb_tip = |(bone, _): &(&HandBone, _)| matches!(bone, HandBone::ThumbTip);
+        let thumb_tip = match pinch.hand {
+            Hand::Left => left_hand.iter().find(is_thumb_tip),
+            Hand::Right => right_hand.iter().find(is_thumb_tip),
+        };
+        let Some((_, thumb_tip)) = thumb_tip else {
+            continue;
+        };
+        let distance = index_tip.translation().distance(thumb_tip.translation());
+        if let Some(event) = pinch.update(distance, now, &settings) {
+            events.send(event);
+        }
+    }
+}
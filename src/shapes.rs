@@ -0,0 +1,199 @@
+//! The primitive shapes blocks can be made in.
+//!
+//! Every shape is fitted into the box spanned by the creation gesture, so the same stretch makes a
+//! cuboid, sphere, cylinder, capsule or wedge ramp with matching mesh, collider and gizmo preview.
+
+use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy_xpbd_3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Smallest extent of a shape along any axis, so thin stretches still make a valid collider.
+pub const MIN_EXTENT: f32 = 0.01;
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ShapeKind {
+    #[default]
+    Cuboid,
+    /// A sphere with the diameter of the longest side of the box.
+    Sphere,
+    /// A cylinder standing along the box's Y axis.
+    Cylinder,
+    /// A capsule standing along the box's Y axis.
+    Capsule,
+    /// A ramp rising from the front to the full height at the back (-Z) of the box.
+    Wedge,
+}
+
+/// The shape new blocks are made in.
+#[derive(Resource, Deref, DerefMut, Copy, Clone, Default)]
+pub struct SelectedShape(pub ShapeKind);
+
+impl ShapeKind {
+    pub const ALL: [ShapeKind; 5] = [
+        ShapeKind::Cuboid,
+        ShapeKind::Sphere,
+        ShapeKind::Cylinder,
+        ShapeKind::Capsule,
+        ShapeKind::Wedge,
+    ];
+
+    pub fn mesh(self, size: Vec3) -> Mesh {
+        let size = size.max(Vec3::splat(MIN_EXTENT));
+        match self {
+            ShapeKind::Cuboid => Cuboid::from_size(size).into(),
+            ShapeKind::Sphere => Sphere::new(sphere_radius(size)).mesh().uv(32, 18),
+            ShapeKind::Cylinder => {
+                let (radius, height) = round_dimensions(size);
+                Cylinder::new(radius, height).into()
+            }
+            ShapeKind::Capsule => {
+                let (radius, height) = round_dimensions(size);
+                Capsule3d::new(radius, capsule_length(radius, height)).into()
+            }
+            ShapeKind::Wedge => wedge_mesh(size),
+        }
+    }
+
+    pub fn collider(self, size: Vec3) -> Collider {
+        let size = size.max(Vec3::splat(MIN_EXTENT));
+        match self {
+            ShapeKind::Cuboid => Collider::cuboid(size.x, size.y, size.z),
+            ShapeKind::Sphere => Collider::sphere(sphere_radius(size)),
+            ShapeKind::Cylinder => {
+                let (radius, height) = round_dimensions(size);
+                Collider::cylinder(height, radius)
+            }
+            ShapeKind::Capsule => {
+                let (radius, height) = round_dimensions(size);
+                Collider::capsule(capsule_length(radius, height), radius)
+            }
+            ShapeKind::Wedge => Collider::convex_hull(wedge_corners(size).to_vec())
+                .unwrap_or_else(|| Collider::cuboid(size.x, size.y, size.z)),
+        }
+    }
+
+    /// Volume in cubic meters of the shape fitted into a box of `size`.
+    pub fn volume(self, size: Vec3) -> f32 {
+        use std::f32::consts::PI;
+        let size = size.max(Vec3::splat(MIN_EXTENT));
+        match self {
+            ShapeKind::Cuboid => size.x * size.y * size.z,
+            ShapeKind::Sphere => 4.0 / 3.0 * PI * sphere_radius(size).powi(3),
+            ShapeKind::Cylinder => {
+                let (radius, height) = round_dimensions(size);
+                PI * radius * radius * height
+            }
+            ShapeKind::Capsule => {
+                let (radius, height) = round_dimensions(size);
+                PI * radius * radius * (capsule_length(radius, height) + 4.0 / 3.0 * radius)
+            }
+            ShapeKind::Wedge => size.x * size.y * size.z / 2.0,
+        }
+    }
+
+    /// Draws the outline of the shape fitted into a box of `size` at `transform`, ignoring its
+    /// scale.
+    pub fn draw_gizmo(self, gizmos: &mut Gizmos, transform: Transform, size: Vec3, color: Color) {
+        let size = size.max(Vec3::splat(MIN_EXTENT));
+        let Transform {
+            translation,
+            rotation,
+            ..
+        } = transform;
+        match self {
+            ShapeKind::Cuboid => {
+                gizmos.cuboid(transform.with_scale(size), color);
+            }
+            ShapeKind::Sphere => {
+                gizmos.sphere(translation, rotation, sphere_radius(size), color);
+            }
+            ShapeKind::Cylinder => {
+                let (radius, height) = round_dimensions(size);
+                gizmos.primitive_3d(Cylinder::new(radius, height), translation, rotation, color);
+            }
+            ShapeKind::Capsule => {
+                let (radius, height) = round_dimensions(size);
+                gizmos.primitive_3d(
+                    Capsule3d::new(radius, capsule_length(radius, height)),
+                    translation,
+                    rotation,
+                    color,
+                );
+            }
+            ShapeKind::Wedge => {
+                let [a, b, c, d, e, f] =
+                    wedge_corners(size).map(|corner| translation + rotation * corner);
+                gizmos.linestrip([a, b, c, d, a, e, f, b], color);
+                gizmos.line(d, e, color);
+                gizmos.line(c, f, color);
+            }
+        }
+    }
+}
+
+fn sphere_radius(size: Vec3) -> f32 {
+    size.max_element() / 2.0
+}
+
+/// Radius and total height of a round shape standing along Y.
+fn round_dimensions(size: Vec3) -> (f32, f32) {
+    (size.x.max(size.z) / 2.0, size.y)
+}
+
+/// Length of the capsule's cylindrical part, so its total height matches the box where possible.
+fn capsule_length(radius: f32, height: f32) -> f32 {
+    (height - radius * 2.0).max(0.0)
+}
+
+/// The bottom four corners followed by the two top back corners of a wedge.
+fn wedge_corners(size: Vec3) -> [Vec3; 6] {
+    let Vec3 { x, y, z } = size / 2.0;
+    [
+        Vec3::new(-x, -y, -z),
+        Vec3::new(x, -y, -z),
+        Vec3::new(x, -y, z),
+        Vec3::new(-x, -y, z),
+        Vec3::new(-x, y, -z),
+        Vec3::new(x, y, -z),
+    ]
+}
+
+fn wedge_mesh(size: Vec3) -> Mesh {
+    let [a, b, c, d, e, f] = wedge_corners(size);
+    // Every face is wound counter clockwise when seen from outside
+    let faces: [&[Vec3]; 5] = [
+        &[a, b, c, d],
+        &[a, e, f, b],
+        &[d, c, f, e],
+        &[a, d, e],
+        &[b, f, c],
+    ];
+
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut indices = Vec::new();
+    for face in faces {
+        let normal = (face[1] - face[0]).cross(face[2] - face[0]).normalize();
+        let start = positions.len() as u32;
+        for (i, corner) in face.iter().enumerate() {
+            positions.push(corner.to_array());
+            normals.push(normal.to_array());
+            uvs.push([[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]][i]);
+        }
+        for i in 1..face.len() as u32 - 1 {
+            indices.extend([start, start + i, start + i + 1]);
+        }
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
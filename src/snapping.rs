@@ -0,0 +1,240 @@
+//! Snapping new blocks to a grid and onto the surfaces around them so they line up.
+//!
+//! With [`SnapSettings::enabled`] the size of a block is rounded to whole grid cells, its rotation
+//! to multiples of [`SnapSettings::angle_step`] and its position so the box around it starts on a
+//! grid line. With [`SurfaceSnapSettings::enabled`], which is off until asked for, a block close
+//! to another block or the floor is then turned to face it and moved flush against it.
+//! [`BlockPlacement`] does both, so the preview and the spawned block are snapped the same way.
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+
+use crate::layers::Layer;
+use crate::shapes::ShapeKind;
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct SnapSettings {
+    pub enabled: bool,
+    /// Size of a grid cell in meters.
+    pub grid_size: f32,
+    /// Rotations are rounded to multiples of this many radians around each axis.
+    pub angle_step: f32,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            grid_size: 0.02,
+            angle_step: 15f32.to_radians(),
+        }
+    }
+}
+
+impl SnapSettings {
+    /// The grid sizes worth switching between, in meters.
+    pub const GRID_SIZES: [f32; 3] = [0.01, 0.02, 0.05];
+
+    /// Switches to the next of [`Self::GRID_SIZES`].
+    pub fn cycle_grid_size(&mut self) {
+        let next = Self::GRID_SIZES
+            .iter()
+            .position(|size| (size - self.grid_size).abs() < 1e-4)
+            .map_or(0, |i| (i + 1) % Self::GRID_SIZES.len());
+        self.grid_size = Self::GRID_SIZES[next];
+    }
+
+    fn round(value: f32, step: f32) -> f32 {
+        (value / step).round() * step
+    }
+
+    /// Snaps a block of `size` at `transform`, returning the snapped transform and size. Does
+    /// nothing unless snapping is enabled.
+    pub fn snap(&self, transform: Transform, size: Vec3) -> (Transform, Vec3) {
+        if !self.enabled || self.grid_size <= 0.0 {
+            return (transform, size);
+        }
+        let grid = self.grid_size;
+        let size = (size / grid).round().max(Vec3::ONE) * grid;
+
+        let (y, x, z) = transform.rotation.to_euler(EulerRot::YXZ);
+        let rotation = if self.angle_step > 0.0 {
+            let [y, x, z] = [y, x, z].map(|angle| Self::round(angle, self.angle_step));
+            Quat::from_euler(EulerRot::YXZ, y, x, z)
+        } else {
+            transform.rotation
+        };
+
+        // Put the corner of the box around the block on the grid, so neighbouring blocks of whole
+        // cells share faces
+        let matrix = Mat3::from_quat(rotation);
+        let half_extents = matrix.x_axis.abs() * size.x / 2.0
+            + matrix.y_axis.abs() * size.y / 2.0
+            + matrix.z_axis.abs() * size.z / 2.0;
+        let corner = transform.translation - half_extents;
+        let corner = (corner / grid).round() * grid;
+
+        let transform = Transform {
+            translation: corner + half_extents,
+            rotation,
+            scale: size,
+        };
+        (transform, size)
+    }
+}
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct SurfaceSnapSettings {
+    /// Off by default, so blocks land where they are made until surface snapping is turned on.
+    pub enabled: bool,
+    /// How far in meters a block can be from a surface to snap onto it.
+    pub max_distance: f32,
+    pub indicator_color: Color,
+}
+
+impl Default for SurfaceSnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_distance: 0.05,
+            indicator_color: Color::rgb_u8(0, 200, 255),
+        }
+    }
+}
+
+/// Where a snapped block touches the surface it was snapped onto.
+#[derive(Copy, Clone, Debug)]
+pub struct SurfaceContact {
+    pub entity: Entity,
+    pub point: Vec3,
+    /// Points out of the surface, towards the block.
+    pub normal: Vec3,
+}
+
+/// A block's final transform and size after snapping.
+#[derive(Copy, Clone, Debug)]
+pub struct Placement {
+    pub transform: Transform,
+    pub size: Vec3,
+    pub contact: Option<SurfaceContact>,
+}
+
+/// Everything needed to snap a new block into place.
+#[derive(SystemParam)]
+pub struct BlockPlacement<'w, 's> {
+    pub snap: Res<'w, SnapSettings>,
+    pub surface_snap: Res<'w, SurfaceSnapSettings>,
+    spatial_query: SpatialQuery<'w, 's>,
+    colliders: Query<'w, 's, (&'static Position, &'static Rotation)>,
+}
+
+impl BlockPlacement<'_, '_> {
+    /// Snaps a `shape` block of `size` at `transform` to the grid and onto a nearby surface.
+    pub fn place(&self, shape: ShapeKind, transform: Transform, size: Vec3) -> Placement {
+        let (transform, size) = self.snap.snap(transform, size);
+        let on_surface = self
+            .surface_snap
+            .enabled
+            .then(|| self.snap_to_surface(shape, transform, size))
+            .flatten();
+        let (transform, contact) = match on_surface {
+            Some((transform, contact)) => (transform, Some(contact)),
+            None => (transform, None),
+        };
+        Placement {
+            transform,
+            size,
+            contact,
+        }
+    }
+
+    fn cast(
+        &self,
+        collider: &Collider,
+        transform: Transform,
+        direction: Direction3d,
+    ) -> Option<ShapeHitData> {
+        self.spatial_query.cast_shape(
+            collider,
+            transform.translation,
+            transform.rotation,
+            direction,
+            self.surface_snap.max_distance,
+            true,
+            SpatialQueryFilter::from_mask([Layer::Blocks, Layer::Floor]),
+        )
+    }
+
+    fn snap_to_surface(
+        &self,
+        shape: ShapeKind,
+        transform: Transform,
+        size: Vec3,
+    ) -> Option<(Transform, SurfaceContact)> {
+        let collider = shape.collider(size);
+        // Look along the world axes and the block's own axes for the closest surface
+        let axes = [Vec3::X, Vec3::Y, Vec3::Z]
+            .into_iter()
+            .chain([Vec3::X, Vec3::Y, Vec3::Z].map(|axis| transform.rotation * axis));
+        let closest = axes
+            .flat_map(|axis| [axis, -axis])
+            .filter_map(|axis| Direction3d::new(axis).ok())
+            .filter_map(|direction| self.cast(&collider, transform, direction))
+            .min_by(|a, b| a.time_of_impact.total_cmp(&b.time_of_impact))?;
+        // Hits are in the space of the collider that was hit
+        let (_, surface_rotation) = self.colliders.get(closest.entity).ok()?;
+        let normal = (surface_rotation.0 * closest.normal1).try_normalize()?;
+
+        // Turn the block so the side facing the surface lies flat against it
+        let facing = [
+            Vec3::X,
+            Vec3::Y,
+            Vec3::Z,
+            Vec3::NEG_X,
+            Vec3::NEG_Y,
+            Vec3::NEG_Z,
+        ]
+        .map(|axis| transform.rotation * axis)
+        .into_iter()
+        .max_by(|a, b| a.dot(-normal).total_cmp(&b.dot(-normal)))?;
+        let rotation = Quat::from_rotation_arc(facing, -normal) * transform.rotation;
+        let turned = Transform {
+            rotation,
+            ..transform
+        };
+
+        let direction = Direction3d::new(-normal).ok()?;
+        let hit = self.cast(&collider, turned, direction)?;
+        let (surface_position, surface_rotation) = self.colliders.get(hit.entity).ok()?;
+        let transform = Transform {
+            translation: turned.translation - normal * hit.time_of_impact,
+            ..turned
+        };
+        Some((
+            transform,
+            SurfaceContact {
+                entity: hit.entity,
+                point: surface_position.0 + surface_rotation.0 * hit.point1,
+                normal,
+            },
+        ))
+    }
+}
+
+impl Placement {
+    /// Marks where the block touches the surface it was snapped onto.
+    pub fn draw_indicator(&self, gizmos: &mut Gizmos, color: Color) {
+        let Some(contact) = self.contact else { return };
+        let Ok(normal) = Direction3d::new(contact.normal) else {
+            return;
+        };
+        let radius = self.size.max_element() / 2.0;
+        gizmos.circle(contact.point, normal, radius, color);
+        gizmos.arrow(
+            contact.point,
+            contact.point + contact.normal * radius,
+            color,
+        );
+    }
+}
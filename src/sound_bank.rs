@@ -0,0 +1,129 @@
+//! The impact sounds for every pair of surfaces that can hit each other.
+//!
+//! [`ImpactSoundBank`] holds a few clips per pair of [`Surface`]s, so a metal block landing on the
+//! floor sounds different from it knocking into a foam block or a hand. Every hit picks one of the
+//! pair's clips at random and nudges its pitch a little, so repeated hits don't sound identical.
+//!
+//! The clips in `assets/impacts` are synthesized from a few damped resonances and a burst of noise
+//! per material, three takes each with slightly different resonances.
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use random_number::random;
+
+use crate::block_material::BlockMaterial;
+
+/// What something that makes impact sounds is made of.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Surface {
+    Block(BlockMaterial),
+    Floor,
+    Hand,
+}
+
+/// One way a hit can sound.
+#[derive(Clone, Debug)]
+pub struct ImpactClip {
+    pub source: Handle<AudioSource>,
+    /// Playback speed before the random pitch variation.
+    pub speed: f32,
+    /// Multiplier on the impact volume.
+    pub volume: f32,
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct ImpactSoundBank {
+    entries: HashMap<(Surface, Surface), Vec<ImpactClip>>,
+    /// Played for pairs without an entry.
+    pub fallback: Vec<ImpactClip>,
+    /// How much the pitch of each hit randomly deviates, as a fraction of its speed.
+    pub pitch_variation: f32,
+}
+
+/// Pairs are the same whichever surface comes first.
+fn key(a: Surface, b: Surface) -> (Surface, Surface) {
+    (a.min(b), a.max(b))
+}
+
+impl ImpactSoundBank {
+    pub fn new(fallback: Vec<ImpactClip>) -> Self {
+        Self {
+            entries: HashMap::default(),
+            fallback,
+            pitch_variation: 0.08,
+        }
+    }
+
+    /// Replaces the clips played when `a` and `b` hit each other.
+    pub fn insert(&mut self, a: Surface, b: Surface, clips: Vec<ImpactClip>) {
+        self.entries.insert(key(a, b), clips);
+    }
+
+    pub fn clips(&self, a: Surface, b: Surface) -> &[ImpactClip] {
+        self.entries
+            .get(&key(a, b))
+            .filter(|clips| !clips.is_empty())
+            .unwrap_or(&self.fallback)
+    }
+
+    /// Picks a random clip for `a` hitting `b` and returns it with a randomly varied speed.
+    pub fn pick(&self, a: Surface, b: Surface) -> Option<(&ImpactClip, f32)> {
+        let clips = self.clips(a, b);
+        if clips.is_empty() {
+            return None;
+        }
+        let clip = &clips[random!(0..clips.len())];
+        let variation = random!(-1.0f32..=1.0) * self.pitch_variation;
+        Some((clip, clip.speed * (1.0 + variation)))
+    }
+}
+
+/// Takes of something hitting the floor, played along with the block's own sound.
+const FLOOR_SOUNDS: [&str; 3] = [
+    "embedded://impacts/floor-1.wav",
+    "embedded://impacts/floor-2.wav",
+    "embedded://impacts/floor-3.wav",
+];
+
+impl FromWorld for ImpactSoundBank {
+    /// Builds the bank from the embedded clips, a few takes per material and of the floor.
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let clips = |paths: [&'static str; 3], speed: f32, volume: f32| {
+            paths.map(|path| ImpactClip {
+                source: asset_server.load(path),
+                speed,
+                volume,
+            })
+        };
+
+        let mut bank = Self::new(vec![ImpactClip {
+            source: asset_server.load("embedded://plastic-hit.ogg"),
+            speed: 1.0,
+            volume: 1.0,
+        }]);
+        for (i, a) in BlockMaterial::ALL.into_iter().enumerate() {
+            let sound = a.impact_sound();
+            // The floor is solid and deep, hands are soft and muffle the hit
+            let mut floor = clips(sound.paths, sound.speed, sound.volume).to_vec();
+            floor.extend(clips(FLOOR_SOUNDS, 1.0, sound.volume * 1.2));
+            bank.insert(Surface::Block(a), Surface::Floor, floor);
+            bank.insert(
+                Surface::Block(a),
+                Surface::Hand,
+                clips(sound.paths, sound.speed * 0.7, sound.volume * 0.4).to_vec(),
+            );
+            // Each unordered pair once, both blocks ring when they hit each other
+            for b in &BlockMaterial::ALL[i..] {
+                let other = b.impact_sound();
+                let volume = sound.volume.min(other.volume);
+                let mut pair = clips(sound.paths, sound.speed, volume).to_vec();
+                if *b != a {
+                    pair.extend(clips(other.paths, other.speed, volume));
+                }
+                bank.insert(Surface::Block(a), Surface::Block(*b), pair);
+            }
+        }
+        bank
+    }
+}
@@ -0,0 +1,277 @@
+//! Simulated hands for exercising the gesture systems without a headset.
+//!
+//! [`SimulatedHandsPlugin`] spawns fake [`HandBone`] entities for both hands and moves them along
+//! the [`Trajectory`]s stored in [`SimulatedHands`], so integration tests can pinch, stretch and
+//! release in a [`headless_app`].
+
+use std::time::Duration;
+
+use bevy::asset::AssetPlugin;
+use bevy::audio::AudioSource;
+use bevy::gizmos::GizmoPlugin;
+use bevy::hierarchy::HierarchyPlugin;
+use bevy::prelude::*;
+use bevy::render::render_resource::Shader;
+use bevy::time::TimeUpdateStrategy;
+use bevy::transform::TransformPlugin;
+use bevy_xpbd_3d::prelude::*;
+use bevy_xr::hands::{HandBone, HandBoneRadius, LeftHand, RightHand};
+
+use crate::hand_recording::{HandFrame, HandRecording, RecordedBone};
+use crate::hands::Hand;
+
+/// Length of a single [`App::update`] in a [`headless_app`].
+pub const FRAME_TIME: Duration = Duration::from_micros(16_667);
+
+/// The bones spawned for every simulated hand.
+pub const SIMULATED_BONES: [HandBone; 7] = [
+    HandBone::Palm,
+    HandBone::Wrist,
+    HandBone::ThumbTip,
+    HandBone::IndexTip,
+    HandBone::MiddleTip,
+    HandBone::RingTip,
+    HandBone::LittleTip,
+];
+
+/// Creates an app with everything the crate's plugins need to run without a window, headset or
+/// audio device, advancing time by [`FRAME_TIME`] on every update.
+///
+/// Add [`SimulatedHandsPlugin`] or a [`HandReplayPlugin`](crate::hand_recording::HandReplayPlugin)
+/// to give it hands.
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        AssetPlugin::default(),
+    ))
+    .init_asset::<Shader>()
+    .init_asset::<Mesh>()
+    .init_asset::<Image>()
+    .init_asset::<StandardMaterial>()
+    .init_asset::<AudioSource>()
+    .add_plugins((GizmoPlugin, PhysicsPlugins::default()))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(FRAME_TIME));
+    app
+}
+
+/// Runs `app` until `seconds` of simulated time have passed.
+pub fn run_for(app: &mut App, seconds: f32) {
+    let frames = (seconds / FRAME_TIME.as_secs_f32()).ceil() as usize;
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+pub struct SimulatedHandsPlugin;
+
+impl Plugin for SimulatedHandsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SimulatedHands>();
+        app.add_systems(Startup, spawn_simulated_hands);
+        app.add_systems(PreUpdate, drive_simulated_hands);
+    }
+}
+
+/// Marks a bone entity spawned by [`SimulatedHandsPlugin`].
+#[derive(Component, Copy, Clone, Debug)]
+pub struct SimulatedBone(pub Hand);
+
+/// World space poses of the bones the gesture systems look at.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HandPose {
+    pub palm: Transform,
+    pub thumb_tip: Vec3,
+    pub index_tip: Vec3,
+}
+
+impl HandPose {
+    /// A pose with thumb and index tip touching at `pinch_point`.
+    pub fn pinching(pinch_point: Vec3) -> Self {
+        Self {
+            palm: Transform::from_translation(pinch_point + Vec3::new(0.0, -0.03, 0.07)),
+            thumb_tip: pinch_point + Vec3::new(0.0, -0.01, 0.0),
+            index_tip: pinch_point,
+        }
+    }
+
+    /// A pose with the index tip at `index_tip` and the thumb spread away from it.
+    pub fn open(index_tip: Vec3) -> Self {
+        Self {
+            thumb_tip: index_tip + Vec3::new(0.0, -0.1, 0.03),
+            ..Self::pinching(index_tip)
+        }
+    }
+
+    /// Returns where `bone` is in this pose, placing the remaining finger tips next to the index
+    /// tip.
+    pub fn bone_transform(&self, bone: HandBone) -> Transform {
+        let tip = |offset: Vec3| {
+            Transform::from_translation(self.index_tip + self.palm.rotation * offset)
+        };
+        match bone {
+            HandBone::Palm => self.palm,
+            HandBone::Wrist => self.palm * Transform::from_xyz(0.0, 0.0, 0.08),
+            HandBone::ThumbTip => Transform::from_translation(self.thumb_tip),
+            HandBone::IndexTip => Transform::from_translation(self.index_tip),
+            HandBone::MiddleTip => tip(Vec3::new(0.02, 0.0, 0.0)),
+            HandBone::RingTip => tip(Vec3::new(0.04, 0.0, 0.01)),
+            HandBone::LittleTip => tip(Vec3::new(0.06, 0.0, 0.02)),
+            _ => self.palm,
+        }
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            palm: Transform {
+                translation: self.palm.translation.lerp(other.palm.translation, t),
+                rotation: self.palm.rotation.slerp(other.palm.rotation, t),
+                scale: self.palm.scale.lerp(other.palm.scale, t),
+            },
+            thumb_tip: self.thumb_tip.lerp(other.thumb_tip, t),
+            index_tip: self.index_tip.lerp(other.index_tip, t),
+        }
+    }
+}
+
+/// A scripted sequence of hand poses, linearly interpolated between keyframes.
+#[derive(Clone, Debug, Default)]
+pub struct Trajectory {
+    keyframes: Vec<(f32, HandPose)>,
+}
+
+impl Trajectory {
+    pub fn new(start: HandPose) -> Self {
+        Self {
+            keyframes: vec![(0.0, start)],
+        }
+    }
+
+    /// Moves to `pose` over `duration` seconds, starting where the previous keyframe ended.
+    pub fn then(mut self, duration: f32, pose: HandPose) -> Self {
+        let last = self.keyframes.last().map_or(0.0, |(time, _)| *time);
+        self.keyframes.push((last + duration, pose));
+        self
+    }
+
+    /// Keeps the last pose for `duration` seconds.
+    pub fn hold(self, duration: f32) -> Self {
+        let Some((_, pose)) = self.keyframes.last().copied() else {
+            return self;
+        };
+        self.then(duration, pose)
+    }
+
+    /// Length of the trajectory in seconds.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |(time, _)| *time)
+    }
+
+    pub fn sample(&self, time: f32) -> Option<HandPose> {
+        let next = self.keyframes.iter().position(|(t, _)| *t > time);
+        match next {
+            None => self.keyframes.last().map(|(_, pose)| *pose),
+            Some(0) => self.keyframes.first().map(|(_, pose)| *pose),
+            Some(i) => {
+                let (start_time, start) = self.keyframes[i - 1];
+                let (end_time, end) = self.keyframes[i];
+                Some(start.lerp(&end, (time - start_time) / (end_time - start_time)))
+            }
+        }
+    }
+}
+
+/// The trajectories the simulated hands follow, sampled at the app's elapsed time.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct SimulatedHands {
+    pub left: Trajectory,
+    pub right: Trajectory,
+}
+
+impl SimulatedHands {
+    /// Both hands pinch together at `start`, stretch apart to `left_end` and `right_end`, hold
+    /// there and let go.
+    pub fn pinch_stretch(start: Vec3, left_end: Vec3, right_end: Vec3) -> Self {
+        let stretch = |end: Vec3| {
+            Trajectory::new(HandPose::pinching(start))
+                .hold(0.2)
+                .then(0.4, HandPose::pinching(end))
+                .hold(0.2)
+                .then(0.1, HandPose::open(end))
+        };
+        Self {
+            left: stretch(left_end),
+            right: stretch(right_end),
+        }
+    }
+
+    /// Samples both trajectories every [`FRAME_TIME`] into a recording of the simulated bones.
+    pub fn to_recording(&self) -> HandRecording {
+        let duration = self.left.duration().max(self.right.duration());
+        let frame_count = (duration / FRAME_TIME.as_secs_f32()).ceil() as usize + 1;
+        let frames = (0..frame_count)
+            .map(|i| {
+                let time = i as f32 * FRAME_TIME.as_secs_f32();
+                let mut bones = Vec::new();
+                for (hand, trajectory) in [(Hand::Left, &self.left), (Hand::Right, &self.right)] {
+                    let Some(pose) = trajectory.sample(time) else {
+                        continue;
+                    };
+                    bones.extend(SIMULATED_BONES.map(|bone| RecordedBone {
+                        hand,
+                        bone,
+                        radius: 0.01,
+                        transform: pose.bone_transform(bone),
+                    }));
+                }
+                HandFrame { time, bones }
+            })
+            .collect();
+        HandRecording { frames }
+    }
+}
+
+fn spawn_simulated_hands(mut commands: Commands) {
+    for bone in SIMULATED_BONES {
+        commands.spawn((
+            bone,
+            LeftHand,
+            SimulatedBone(Hand::Left),
+            HandBoneRadius(0.01),
+            SpatialBundle::default(),
+        ));
+        commands.spawn((
+            bone,
+            RightHand,
+            SimulatedBone(Hand::Right),
+            HandBoneRadius(0.01),
+            SpatialBundle::default(),
+        ));
+    }
+}
+
+fn drive_simulated_hands(
+    time: Res<Time>,
+    hands: Res<SimulatedHands>,
+    mut bones: Query<(
+        &HandBone,
+        &SimulatedBone,
+        &mut Transform,
+        &mut GlobalTransform,
+    )>,
+) {
+    let time = time.elapsed_seconds();
+    let left = hands.left.sample(time);
+    let right = hands.right.sample(time);
+    for (bone, simulated, mut transform, mut global_transform) in &mut bones {
+        let pose = match simulated.0 {
+            Hand::Left => left,
+            Hand::Right => right,
+        };
+        let Some(pose) = pose else { continue };
+        *transform = pose.bone_transform(*bone);
+        *global_transform = GlobalTransform::from(*transform);
+    }
+}
@@ -0,0 +1,381 @@
+//! Buttons, toggles, sliders and panels floating in the world, poked with the index tips.
+//!
+//! A [`Widget`] is a rectangle on the XY plane of its entity, facing +Z. An index tip in front of
+//! it hovers it, pushing it in by [`WidgetSettings::press_depth`] presses it with a click and
+//! pulling back out releases it. A tip has to arrive from the front to press, so reaching through
+//! a widget to the one behind it does nothing. Widgets are drawn with gizmos and report what
+//! happens to them with [`ButtonClicked`], [`ToggleChanged`] and [`SliderChanged`] events.
+
+use bevy::audio::{PlaybackMode, Volume};
+use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::*;
+use bevy_xr::hands::HandBone;
+
+use crate::gizmo_text::{draw_text, TextAnchor};
+use crate::hands::{HandsPlugin, HandsSet, HandsState};
+use crate::layers::Layer;
+
+pub struct WidgetPlugin;
+
+impl Plugin for WidgetPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<HandsPlugin>() {
+            app.add_plugins(HandsPlugin);
+        }
+        app.init_resource::<WidgetSettings>();
+        app.add_event::<ButtonClicked>();
+        app.add_event::<ToggleChanged>();
+        app.add_event::<SliderChanged>();
+        app.add_systems(
+            Update,
+            (add_widget_states, poke_widgets, draw_panels, draw_widgets)
+                .chain()
+                .in_set(WidgetSet)
+                .after(HandsSet),
+        );
+    }
+}
+
+/// Moving and enabling widgets should happen before this set, reacting to their events after it.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WidgetSet;
+
+#[derive(Resource, Copy, Clone, Debug)]
+pub struct WidgetSettings {
+    /// How far in meters in front of a widget an index tip hovers it.
+    pub hover_distance: f32,
+    /// How far in meters a widget has to be pushed in to press it.
+    pub press_depth: f32,
+    /// A pressed widget is released once it is pushed in less than this many meters.
+    pub release_depth: f32,
+    /// How far in meters a widget can be pushed in before the finger goes through it.
+    pub travel: f32,
+    /// How far in meters a finger can be off the edges of a widget and still touch it.
+    pub margin: f32,
+    /// Height of widget labels in meters.
+    pub text_height: f32,
+    pub color: Color,
+    pub hover_color: Color,
+    /// Color of pressed buttons, toggles that are on and the filled part of sliders.
+    pub active_color: Color,
+    pub click_volume: f32,
+    pub click_speed: f32,
+}
+
+impl Default for WidgetSettings {
+    fn default() -> Self {
+        Self {
+            hover_distance: 0.03,
+            press_depth: 0.006,
+            release_depth: 0.003,
+            travel: 0.01,
+            margin: 0.003,
+            text_height: 0.006,
+            color: Color::rgb(0.6, 0.6, 0.65),
+            hover_color: Color::WHITE,
+            active_color: Color::rgb_u8(0, 200, 255),
+            click_volume: 0.4,
+            click_speed: 2.5,
+        }
+    }
+}
+
+/// A pokeable rectangle, combine with [`PokeButton`], [`Toggle`] or [`Slider`] to give it
+/// behaviour.
+#[derive(Component, Clone, Debug)]
+pub struct Widget {
+    pub size: Vec2,
+    pub label: String,
+    /// Disabled widgets ignore fingers and aren't drawn.
+    pub enabled: bool,
+}
+
+impl Widget {
+    pub fn new(size: Vec2, label: impl Into<String>) -> Self {
+        Self {
+            size,
+            label: label.into(),
+            enabled: true,
+        }
+    }
+}
+
+/// How a finger is touching a widget, added to every [`Widget`].
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq)]
+pub struct WidgetState {
+    pub hovered: bool,
+    pub pressed: bool,
+    /// How far in meters the widget is pushed in.
+    pub depth: f32,
+    /// A finger came in from the front of the widget and hasn't left it since, so pushing it in
+    /// presses it.
+    armed: bool,
+}
+
+/// Sends [`ButtonClicked`] when pressed.
+#[derive(Component, Copy, Clone, Debug, Default)]
+pub struct PokeButton;
+
+/// Flips and sends [`ToggleChanged`] when pressed.
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Toggle(pub bool);
+
+/// Follows the finger along its X axis while pressed, sending [`SliderChanged`].
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub struct Slider {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Values are rounded to multiples of this above `min`, zero for no rounding.
+    pub step: f32,
+}
+
+impl Slider {
+    /// Fraction of the way from `min` to `max`.
+    pub fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            ((self.value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// The value at `fraction` of the way from `min` to `max`, rounded to `step`.
+    pub fn value_at(&self, fraction: f32) -> f32 {
+        let value = self.min + fraction.clamp(0.0, 1.0) * (self.max - self.min);
+        if self.step > 0.0 {
+            (self.min + ((value - self.min) / self.step).round() * self.step).min(self.max)
+        } else {
+            value
+        }
+    }
+}
+
+/// A backing plate for a group of widgets, drawn slightly behind its entity's XY plane.
+#[derive(Component, Clone, Debug)]
+pub struct Panel {
+    pub size: Vec2,
+    pub title: String,
+    pub enabled: bool,
+}
+
+impl Panel {
+    pub fn new(size: Vec2, title: impl Into<String>) -> Self {
+        Self {
+            size,
+            title: title.into(),
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Event, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ButtonClicked(pub Entity);
+
+#[derive(Event, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ToggleChanged {
+    pub entity: Entity,
+    pub on: bool,
+}
+
+#[derive(Event, Copy, Clone, Debug, PartialEq)]
+pub struct SliderChanged {
+    pub entity: Entity,
+    pub value: f32,
+}
+
+fn add_widget_states(
+    query: Query<(Entity, &Widget), Without<WidgetState>>,
+    panels: Query<(Entity, &Panel), Without<Collider>>,
+    settings: Res<WidgetSettings>,
+    mut commands: Commands,
+) {
+    for (entity, widget) in &query {
+        commands.entity(entity).insert((
+            WidgetState::default(),
+            ui_collider(widget.size, settings.travel),
+        ));
+    }
+    for (entity, panel) in &panels {
+        commands
+            .entity(entity)
+            .insert(ui_collider(panel.size, settings.travel));
+    }
+}
+
+/// A box as deep as a widget can be pushed in, on [`Layer::Ui`] so nothing physical touches it.
+fn ui_collider(size: Vec2, depth: f32) -> (Collider, CollisionLayers) {
+    (
+        Collider::cuboid(size.x, size.y, depth),
+        Layer::Ui.collision_layers(),
+    )
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn poke_widgets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<WidgetSettings>,
+    hands: Res<HandsState>,
+    mut widgets: Query<(
+        Entity,
+        &Widget,
+        &mut WidgetState,
+        &GlobalTransform,
+        Has<PokeButton>,
+        Option<&mut Toggle>,
+        Option<&mut Slider>,
+    )>,
+    mut clicked: EventWriter<ButtonClicked>,
+    mut toggled: EventWriter<ToggleChanged>,
+    mut slid: EventWriter<SliderChanged>,
+) {
+    let tips: Vec<Vec3> = [hands.left, hands.right]
+        .iter()
+        .filter_map(|hand| hand.joint(HandBone::IndexTip))
+        .map(|tip| tip.translation)
+        .collect();
+
+    for (entity, widget, mut state, transform, button, toggle, slider) in &mut widgets {
+        if !widget.enabled {
+            *state = WidgetState::default();
+            continue;
+        }
+        let inverse = transform.affine().inverse();
+        let half_size = widget.size / 2.0 + settings.margin;
+        // The tip pushing the widget in furthest, in the widget's space
+        let touching = tips
+            .iter()
+            .map(|tip| inverse.transform_point3(*tip))
+            .filter(|local| {
+                // A pressed slider follows the finger past its ends
+                let along = (state.pressed && slider.is_some()) || local.x.abs() <= half_size.x;
+                along
+                    && local.y.abs() <= half_size.y
+                    && local.z <= settings.hover_distance
+                    && local.z >= -settings.travel * 2.0
+            })
+            .min_by(|a, b| a.z.total_cmp(&b.z));
+
+        let was_pressed = state.pressed;
+        let armed = state.armed;
+        state.hovered = touching.is_some();
+        state.depth = touching.map_or(0.0, |local| (-local.z).clamp(0.0, settings.travel));
+        // Stays armed while the finger pushes on past the face, until it leaves the widget
+        state.armed = touching.is_some_and(|local| local.z > 0.0 || armed);
+        if !was_pressed && armed && state.depth >= settings.press_depth {
+            state.pressed = true;
+        } else if was_pressed && state.depth < settings.release_depth {
+            state.pressed = false;
+        }
+
+        if state.pressed && !was_pressed {
+            if button {
+                clicked.send(ButtonClicked(entity));
+            }
+            if let Some(mut toggle) = toggle {
+                toggle.0 = !toggle.0;
+                toggled.send(ToggleChanged {
+                    entity,
+                    on: toggle.0,
+                });
+            }
+            commands.spawn((
+                AudioBundle {
+                    source: asset_server.load("embedded://plastic-hit.ogg"),
+                    settings: PlaybackSettings {
+                        mode: PlaybackMode::Despawn,
+                        volume: Volume::new(settings.click_volume),
+                        speed: settings.click_speed,
+                        paused: false,
+                        spatial: true,
+                        spatial_scale: None,
+                    },
+                },
+                SpatialBundle::from_transform(Transform::from_translation(transform.translation())),
+            ));
+        }
+
+        if let (true, Some(local), Some(mut slider)) = (state.pressed, touching, slider) {
+            let value = slider.value_at(local.x / widget.size.x + 0.5);
+            if value != slider.value {
+                slider.value = value;
+                slid.send(SliderChanged { entity, value });
+            }
+        }
+    }
+}
+
+fn draw_panels(
+    mut gizmos: Gizmos,
+    settings: Res<WidgetSettings>,
+    panels: Query<(&Panel, &GlobalTransform)>,
+) {
+    for (panel, transform) in &panels {
+        if !panel.enabled {
+            continue;
+        }
+        let transform = transform.compute_transform();
+        let back = transform * Transform::from_xyz(0.0, 0.0, -settings.travel);
+        gizmos.rect(back.translation, back.rotation, panel.size, settings.color);
+        if !panel.title.is_empty() {
+            let title =
+                back * Transform::from_xyz(0.0, panel.size.y / 2.0 + settings.text_height, 0.0);
+            draw_text(
+                &mut gizmos,
+                &panel.title,
+                title,
+                settings.text_height,
+                TextAnchor::Center,
+                settings.hover_color,
+            );
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn draw_widgets(
+    mut gizmos: Gizmos,
+    settings: Res<WidgetSettings>,
+    widgets: Query<(
+        &Widget,
+        &WidgetState,
+        &GlobalTransform,
+        Option<&Toggle>,
+        Option<&Slider>,
+    )>,
+) {
+    for (widget, state, transform, toggle, slider) in &widgets {
+        if !widget.enabled {
+            continue;
+        }
+        // The face sinks in as it is pushed
+        let face = transform.compute_transform() * Transform::from_xyz(0.0, 0.0, -state.depth);
+        let active = state.pressed || toggle.is_some_and(|toggle| toggle.0);
+        let color = if active {
+            settings.active_color
+        } else if state.hovered {
+            settings.hover_color
+        } else {
+            settings.color
+        };
+        gizmos.rect(face.translation, face.rotation, widget.size, color);
+
+        if let Some(slider) = slider {
+            // Fill the track up to the value
+            let width = widget.size.x * slider.fraction();
+            let fill = face * Transform::from_xyz((width - widget.size.x) / 2.0, 0.0, 0.0);
+            let size = Vec2::new(width, widget.size.y * 0.8);
+            gizmos.rect(fill.translation, fill.rotation, size, settings.active_color);
+        }
+
+        draw_text(
+            &mut gizmos,
+            &widget.label,
+            face,
+            settings.text_height,
+            TextAnchor::Center,
+            color,
+        );
+    }
+}
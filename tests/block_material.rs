@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::block::{spawn_block, Block};
+use bevy_vr_blocks::block_material::BlockMaterial;
+use bevy_vr_blocks::shapes::ShapeKind;
+use bevy_vr_blocks::test_support::headless_app;
+use bevy_xpbd_3d::prelude::*;
+
+const SIZE: f32 = 0.1;
+
+#[test]
+fn blocks_get_the_physics_of_their_material() {
+    let mut app = headless_app();
+    app.insert_resource(Gravity(Vec3::ZERO));
+    app.add_systems(
+        Startup,
+        |mut commands: Commands,
+         mut meshes: ResMut<Assets<Mesh>>,
+         mut materials: ResMut<Assets<StandardMaterial>>| {
+            for (i, material) in BlockMaterial::ALL.into_iter().enumerate() {
+                spawn_block(
+                    &mut commands,
+                    &mut meshes,
+                    materials.add(material.standard_material(Color::WHITE)),
+                    Block {
+                        shape: ShapeKind::Cuboid,
+                        size: Vec3::splat(SIZE),
+                        material,
+                    },
+                    Transform::from_xyz(i as f32 * 0.5, 1.5, 0.0),
+                );
+            }
+        },
+    );
+    app.update();
+    app.update();
+
+    let world = &mut app.world;
+    let mut blocks = world.query::<(&Block, &Friction, &Restitution, &Mass)>();
+    let mut masses = Vec::new();
+    for (block, friction, restitution, mass) in blocks.iter(world) {
+        let material = block.material;
+        assert_eq!(
+            friction.dynamic_coefficient,
+            material.friction().dynamic_coefficient
+        );
+        assert_eq!(restitution.coefficient, material.restitution().coefficient);
+        let expected = material.density().0 * SIZE.powi(3);
+        assert!(
+            (mass.0 - expected).abs() / expected < 1e-3,
+            "{material:?}: {}",
+            mass.0
+        );
+        masses.push((material, mass.0));
+    }
+    assert_eq!(masses.len(), BlockMaterial::ALL.len());
+
+    let mass_of = |material| masses.iter().find(|(m, _)| *m == material).unwrap().1;
+    assert!(mass_of(BlockMaterial::Metal) > mass_of(BlockMaterial::Wood));
+    assert!(mass_of(BlockMaterial::Foam) < mass_of(BlockMaterial::Wood));
+}
+
+#[test]
+fn materials_differ_where_it_matters() {
+    let ice = BlockMaterial::Ice;
+    let rubber = BlockMaterial::Rubber;
+    assert!(ice.friction().dynamic_coefficient < rubber.friction().dynamic_coefficient);
+    assert!(rubber.restitution().coefficient > BlockMaterial::Foam.restitution().coefficient);
+    assert!(matches!(
+        ice.standard_material(Color::WHITE).alpha_mode,
+        AlphaMode::Blend
+    ));
+    assert_eq!(
+        BlockMaterial::Metal
+            .standard_material(Color::WHITE)
+            .metallic,
+        1.0
+    );
+    // Every material hits with its own pitch
+    for a in BlockMaterial::ALL {
+        for b in BlockMaterial::ALL {
+            if a != b {
+                assert_ne!(a.impact_sound(), b.impact_sound(), "{a:?} and {b:?}");
+            }
+        }
+    }
+}
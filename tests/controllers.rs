@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::block::Block;
+use bevy_vr_blocks::controllers::{ControllerPlugin, ControllerState, Controllers};
+use bevy_vr_blocks::cube_creation::CubeCreationPlugin;
+use bevy_vr_blocks::hands::{Hand, HandsState};
+use bevy_vr_blocks::pinch::PinchEvent;
+use bevy_vr_blocks::test_support::{headless_app, run_for};
+use bevy_xr::hands::HandBone;
+
+/// Both triggers pulled together, stretched apart and let go.
+fn pull_triggers_and_stretch(time: Res<Time>, mut controllers: ResMut<Controllers>) {
+    let t = time.elapsed_seconds();
+    let stretch = ((t - 0.2) / 0.4).clamp(0.0, 1.0);
+    let trigger = if t < 1.0 { 1.0 } else { 0.0 };
+    let controller = |offset: Vec3| {
+        let pose = Transform::from_translation(Vec3::new(0.0, 1.4, -0.4) + offset * stretch);
+        Some(ControllerState {
+            grip: pose,
+            aim: pose,
+            trigger,
+            squeeze: 0.0,
+        })
+    };
+    controllers.0 = [
+        controller(Vec3::new(-0.1, -0.1, 0.0)),
+        controller(Vec3::new(0.1, 0.1, 0.05)),
+    ];
+}
+
+#[test]
+fn controller_triggers_make_a_cube() {
+    let mut app = headless_app();
+    app.add_plugins((ControllerPlugin, CubeCreationPlugin));
+    app.add_systems(PreUpdate, pull_triggers_and_stretch);
+
+    run_for(&mut app, 1.5);
+
+    let mut blocks = app.world.query::<&Block>();
+    let blocks: Vec<_> = blocks.iter(&app.world).collect();
+    assert_eq!(blocks.len(), 1);
+    assert!(blocks[0].size.x > 0.1);
+}
+
+#[test]
+fn controllers_stand_in_for_their_hand() {
+    let mut app = headless_app();
+    app.add_plugins(ControllerPlugin);
+    let grip = Transform::from_xyz(0.2, 1.2, -0.3);
+    let right = |trigger: f32, squeeze: f32| {
+        Controllers([
+            None,
+            Some(ControllerState {
+                grip,
+                aim: grip,
+                trigger,
+                squeeze,
+            }),
+        ])
+    };
+    let pinching = |app: &mut App, controllers: Controllers| {
+        app.insert_resource(controllers);
+        app.update();
+        app.world.resource::<HandsState>().right.pinching
+    };
+
+    // Squeezing closes the hand without pinching
+    assert!(!pinching(&mut app, right(0.5, 1.0)));
+    let hands = *app.world.resource::<HandsState>();
+    assert!(!hands.left.tracked && hands.right.tracked);
+    assert_eq!(hands.right.grab_strength, 1.0);
+    assert_eq!(hands.right.pinch_strength, 0.5);
+    let index_tip = hands.right.joint(HandBone::IndexTip).unwrap();
+    assert!(index_tip.translation.distance(Vec3::new(0.2, 1.2, -0.35)) < 1e-5);
+
+    // The trigger pinches until it is let go past the release threshold
+    assert!(pinching(&mut app, right(0.8, 0.0)));
+    assert!(pinching(&mut app, right(0.5, 0.0)));
+    assert!(!pinching(&mut app, right(0.3, 0.0)));
+    assert!(!pinching(&mut app, right(0.5, 0.0)));
+
+    // Only the hands state is fed, no bones are made up
+    let world = &mut app.world;
+    assert_eq!(world.query::<&HandBone>().iter(world).count(), 0);
+}
+
+#[derive(Resource, Default)]
+struct SentPinches(Vec<PinchEvent>);
+
+#[test]
+fn trigger_pinches_send_pinch_events() {
+    let mut app = headless_app();
+    app.add_plugins(ControllerPlugin);
+    app.init_resource::<SentPinches>();
+    app.add_systems(
+        PostUpdate,
+        |mut events: EventReader<PinchEvent>, mut sent: ResMut<SentPinches>| {
+            sent.0.extend(events.read().copied());
+        },
+    );
+    let left = |trigger: f32| {
+        let grip = Transform::from_xyz(-0.2, 1.2, -0.3);
+        Controllers([
+            Some(ControllerState {
+                grip,
+                aim: grip,
+                trigger,
+                squeeze: 0.0,
+            }),
+            None,
+        ])
+    };
+
+    for controllers in [
+        left(1.0),
+        left(0.5),
+        left(0.0),
+        left(1.0),
+        Controllers::default(),
+    ] {
+        app.insert_resource(controllers);
+        app.update();
+    }
+    assert_eq!(
+        app.world.resource::<SentPinches>().0,
+        [
+            PinchEvent::Held(Hand::Left),
+            PinchEvent::Released(Hand::Left),
+            PinchEvent::Held(Hand::Left),
+            // Putting the controller down lets go of the pinch
+            PinchEvent::Released(Hand::Left),
+        ]
+    );
+}
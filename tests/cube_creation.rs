@@ -0,0 +1,161 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::cube_creation::{CubeCreationPlugin, CubeCreationSettings, CubePreview};
+use bevy_vr_blocks::test_support::{
+    headless_app, run_for, HandPose, SimulatedHands, SimulatedHandsPlugin, Trajectory,
+};
+use bevy_xpbd_3d::prelude::*;
+
+#[test]
+fn pinch_stretch_release_spawns_one_cube() {
+    let mut app = headless_app();
+    app.add_plugins((SimulatedHandsPlugin, CubeCreationPlugin));
+
+    let start = Vec3::new(0.0, 1.5, -0.3);
+    let left_end = Vec3::new(-0.1, 1.4, -0.35);
+    let right_end = Vec3::new(0.1, 1.6, -0.25);
+    let hands = SimulatedHands::pinch_stretch(start, left_end, right_end);
+    let duration = hands.left.duration();
+    app.insert_resource(hands);
+
+    run_for(&mut app, duration + 0.3);
+
+    let world = &mut app.world;
+    let mut cubes = world.query::<(&RigidBody, &Collider)>();
+    let cubes: Vec<_> = cubes
+        .iter(world)
+        .filter(|(body, _)| matches!(body, RigidBody::Dynamic))
+        .collect();
+    assert_eq!(cubes.len(), 1);
+
+    let half_extents = cubes[0]
+        .1
+        .shape()
+        .as_cuboid()
+        .expect("cube collider should be a cuboid")
+        .half_extents;
+    let expected = (right_end - left_end).abs() / 2.0;
+    assert!((half_extents.x - expected.x).abs() < 1e-3);
+    assert!((half_extents.y - expected.y).abs() < 1e-3);
+    assert!((half_extents.z - expected.z).abs() < 1e-3);
+}
+
+/// Stretches a cube out to the two ends with the palms rotated by `left_palm` and
+/// `right_palm`, returning the rotation of the block that was made.
+fn make_cube_with_palms(
+    left_end: Vec3,
+    right_end: Vec3,
+    left_palm: Quat,
+    right_palm: Quat,
+) -> Quat {
+    let mut app = headless_app();
+    app.add_plugins((SimulatedHandsPlugin, CubeCreationPlugin));
+
+    let start = (left_end + right_end) / 2.0;
+    let pose = |pose: HandPose, rotation: Quat| HandPose {
+        palm: pose.palm.with_rotation(rotation),
+        ..pose
+    };
+    let hands = SimulatedHands {
+        left: Trajectory::new(pose(HandPose::pinching(start), left_palm))
+            .hold(0.2)
+            .then(0.4, pose(HandPose::pinching(left_end), left_palm))
+            .hold(0.2)
+            .then(0.1, pose(HandPose::open(left_end), left_palm)),
+        right: Trajectory::new(pose(HandPose::pinching(start), right_palm))
+            .hold(0.2)
+            .then(0.4, pose(HandPose::pinching(right_end), right_palm))
+            .hold(0.2)
+            .then(0.1, pose(HandPose::open(right_end), right_palm)),
+    };
+    let duration = hands.left.duration();
+    app.insert_resource(hands);
+    run_for(&mut app, duration + 0.1);
+
+    let world = &mut app.world;
+    let mut cubes = world.query::<(&RigidBody, &Transform)>();
+    let rotations: Vec<_> = cubes
+        .iter(world)
+        .filter(|(body, _)| matches!(body, RigidBody::Dynamic))
+        .map(|(_, transform)| transform.rotation)
+        .collect();
+    assert_eq!(rotations.len(), 1);
+    rotations[0]
+}
+
+#[test]
+fn tilted_palms_tilt_the_cube() {
+    let tilt = Quat::from_rotation_z(30f32.to_radians());
+    let rotation = make_cube_with_palms(
+        Vec3::new(-0.1, 1.45, -0.25),
+        Vec3::new(0.1, 1.5, -0.35),
+        tilt,
+        tilt,
+    );
+    assert!(rotation.angle_between(tilt) < 0.01, "{rotation:?}");
+}
+
+#[test]
+fn palms_facing_each_other_hold_the_sides_of_the_cube() {
+    // Backs of the hands facing away from each other, fingers pointing forward and up
+    let tilt = Quat::from_rotation_x(20f32.to_radians());
+    let rotation = make_cube_with_palms(
+        Vec3::new(-0.1, 1.45, -0.28),
+        Vec3::new(0.1, 1.5, -0.32),
+        tilt * Quat::from_rotation_z(90f32.to_radians()),
+        tilt * Quat::from_rotation_z(-90f32.to_radians()),
+    );
+    assert!(rotation.angle_between(tilt) < 0.01, "{rotation:?}");
+}
+
+#[test]
+fn switched_off_building_makes_no_cube() {
+    let mut app = headless_app();
+    app.insert_resource(CubeCreationSettings {
+        enabled: false,
+        ..default()
+    });
+    app.add_plugins((SimulatedHandsPlugin, CubeCreationPlugin));
+
+    let start = Vec3::new(0.0, 1.5, -0.3);
+    let left_end = Vec3::new(-0.1, 1.4, -0.35);
+    let right_end = Vec3::new(0.1, 1.6, -0.25);
+    let hands = SimulatedHands::pinch_stretch(start, left_end, right_end);
+    let duration = hands.left.duration();
+    app.insert_resource(hands);
+    run_for(&mut app, duration + 0.3);
+
+    let world = &mut app.world;
+    assert_eq!(world.query::<&RigidBody>().iter(world).count(), 0);
+}
+
+#[test]
+fn losing_a_hand_drops_the_cube() {
+    let mut app = headless_app();
+    app.add_plugins((SimulatedHandsPlugin, CubeCreationPlugin));
+
+    let start = Vec3::new(0.0, 1.5, -0.3);
+    let left_end = Vec3::new(-0.1, 1.4, -0.35);
+    let right_end = Vec3::new(0.1, 1.6, -0.25);
+    app.insert_resource(SimulatedHands {
+        left: Trajectory::new(HandPose::pinching(start))
+            .hold(0.2)
+            .then(0.4, HandPose::pinching(left_end)),
+        right: Trajectory::new(HandPose::pinching(start))
+            .hold(0.2)
+            .then(0.4, HandPose::pinching(right_end))
+            .hold(1.0)
+            .then(0.1, HandPose::open(right_end)),
+    });
+    run_for(&mut app, 0.5);
+    assert!(app.world.resource::<CubePreview>().0.is_some());
+
+    // The left hand drops out of tracking mid stretch and comes back open elsewhere
+    app.world.resource_mut::<SimulatedHands>().left = Trajectory::default();
+    run_for(&mut app, 0.3);
+    assert!(app.world.resource::<CubePreview>().0.is_none());
+    app.world.resource_mut::<SimulatedHands>().left = Trajectory::new(HandPose::open(left_end));
+    run_for(&mut app, 1.0);
+
+    let world = &mut app.world;
+    assert_eq!(world.query::<&RigidBody>().iter(world).count(), 0);
+}
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::block::{spawn_block, Block, BlockDeleted};
+use bevy_vr_blocks::block_material::BlockMaterial;
+use bevy_vr_blocks::deletion::{DeletionPlugin, DeletionSettings, Dissolving};
+use bevy_vr_blocks::shapes::ShapeKind;
+use bevy_vr_blocks::test_support::{
+    headless_app, run_for, HandPose, SimulatedHands, SimulatedHandsPlugin, Trajectory,
+};
+use bevy_xpbd_3d::prelude::*;
+
+#[derive(Resource, Default)]
+struct Deleted(usize);
+
+/// A hand with its palm right under the index tip, so every finger tip is curled up to it.
+fn fist(index_tip: Vec3) -> HandPose {
+    let open = HandPose::open(index_tip);
+    HandPose {
+        palm: Transform::from_translation(index_tip + Vec3::new(0.03, -0.01, 0.0)),
+        ..open
+    }
+}
+
+#[test]
+fn fist_deletes_the_block_it_squeezes() {
+    let index_tip = Vec3::new(0.0, 1.4, -0.3);
+    let block_at = fist(index_tip).palm.translation;
+
+    let mut app = headless_app();
+    app.insert_resource(Gravity(Vec3::ZERO));
+    app.add_plugins((SimulatedHandsPlugin, DeletionPlugin));
+    app.init_resource::<Deleted>();
+    app.add_systems(
+        Startup,
+        move |mut commands: Commands,
+              mut meshes: ResMut<Assets<Mesh>>,
+              mut materials: ResMut<Assets<StandardMaterial>>| {
+            spawn_block(
+                &mut commands,
+                &mut meshes,
+                materials.add(Color::RED),
+                Block {
+                    shape: ShapeKind::Cuboid,
+                    size: Vec3::splat(0.05),
+                    material: BlockMaterial::Wood,
+                },
+                Transform::from_translation(block_at),
+            );
+        },
+    );
+    app.add_systems(
+        PostUpdate,
+        |mut events: EventReader<BlockDeleted>, mut deleted: ResMut<Deleted>| {
+            deleted.0 += events.read().count();
+        },
+    );
+    app.insert_resource(SimulatedHands {
+        left: Trajectory::new(HandPose::open(Vec3::new(-0.5, 1.4, -0.3))),
+        right: Trajectory::new(HandPose::open(index_tip))
+            .hold(0.1)
+            .then(0.1, fist(index_tip)),
+    });
+
+    run_for(&mut app, 0.12);
+    assert_eq!(app.world.resource::<Deleted>().0, 0);
+
+    // Deleted once the fist closes, and shrinking away instead of vanishing
+    run_for(&mut app, 0.18);
+    assert_eq!(app.world.resource::<Deleted>().0, 1);
+    let world = &mut app.world;
+    assert_eq!(world.query::<&Block>().iter(world).count(), 0);
+    assert_eq!(world.query::<&Dissolving>().iter(world).count(), 1);
+
+    let dissolve = app.world.resource::<DeletionSettings>().dissolve_duration;
+    run_for(&mut app, dissolve + 0.1);
+    let world = &mut app.world;
+    assert_eq!(world.query::<&Dissolving>().iter(world).count(), 0);
+    assert_eq!(world.query::<&RigidBody>().iter(world).count(), 0);
+    // Holding the fist doesn't delete anything else
+    assert_eq!(app.world.resource::<Deleted>().0, 1);
+}
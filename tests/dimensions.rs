@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::block::Block;
+use bevy_vr_blocks::block_material::BlockMaterial;
+use bevy_vr_blocks::dimensions::{DimensionReadoutSettings, Units};
+use bevy_vr_blocks::shapes::ShapeKind;
+
+fn wood_cuboid(size: Vec3) -> Block {
+    Block {
+        shape: ShapeKind::Cuboid,
+        size,
+        material: BlockMaterial::Wood,
+    }
+}
+
+fn readout(units: Units, block: &Block) -> String {
+    DimensionReadoutSettings {
+        units,
+        show_volume: true,
+        show_mass: true,
+        ..default()
+    }
+    .format(block)
+}
+
+#[test]
+fn readout_shows_size_volume_and_mass() {
+    let block = wood_cuboid(Vec3::new(0.1, 0.2, 0.1));
+    assert_eq!(
+        readout(Units::Metric, &block),
+        "10.0 × 20.0 × 10.0 cm\n2.00 l\n1.20 kg"
+    );
+    assert_eq!(
+        readout(Units::Imperial, &block),
+        "3.94 × 7.87 × 3.94 in\n122.0 in³\n2.65 lb"
+    );
+}
+
+#[test]
+fn small_blocks_are_read_out_in_small_units() {
+    let block = wood_cuboid(Vec3::new(0.05, 0.04, 0.03));
+    assert_eq!(
+        readout(Units::Metric, &block),
+        "5.0 × 4.0 × 3.0 cm\n60 cm³\n36 g"
+    );
+    assert_eq!(
+        readout(Units::Imperial, &block),
+        "1.97 × 1.57 × 1.18 in\n3.7 in³\n1.3 oz"
+    );
+}
+
+#[test]
+fn a_liter_reads_as_a_liter_in_either_unit() {
+    // Right on the switch from cm³ to liters, rounding decides which side it lands on
+    let text = readout(Units::Metric, &wood_cuboid(Vec3::new(0.1, 0.2, 0.05)));
+    let volume = text.lines().nth(1).unwrap();
+    let liters = if let Some(liters) = volume.strip_suffix(" l") {
+        liters.parse::<f32>().unwrap()
+    } else {
+        let cubic_centimeters = volume.strip_suffix(" cm³").unwrap();
+        cubic_centimeters.parse::<f32>().unwrap() / 1000.0
+    };
+    assert!((liters - 1.0).abs() < 1e-3, "{volume}");
+}
@@ -0,0 +1,125 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::block::{spawn_block, Block};
+use bevy_vr_blocks::block_material::BlockMaterial;
+use bevy_vr_blocks::grab::{GrabPlugin, Grabbed};
+use bevy_vr_blocks::shapes::ShapeKind;
+use bevy_vr_blocks::test_support::{
+    headless_app, run_for, HandPose, SimulatedHands, SimulatedHandsPlugin, Trajectory,
+};
+use bevy_xpbd_3d::prelude::*;
+
+/// Pinch point of a [`HandPose::pinching`] at `index_tip`, halfway to the thumb.
+fn pinch_point(index_tip: Vec3) -> Vec3 {
+    index_tip + Vec3::new(0.0, -0.005, 0.0)
+}
+
+/// An app without gravity that can grab, with a block at `block_at`.
+fn grab_app(block_at: Vec3) -> App {
+    let mut app = headless_app();
+    app.insert_resource(Gravity(Vec3::ZERO));
+    app.add_plugins((SimulatedHandsPlugin, GrabPlugin));
+    app.add_systems(
+        Startup,
+        move |mut commands: Commands,
+              mut meshes: ResMut<Assets<Mesh>>,
+              mut materials: ResMut<Assets<StandardMaterial>>| {
+            spawn_block(
+                &mut commands,
+                &mut meshes,
+                materials.add(Color::RED),
+                Block {
+                    shape: ShapeKind::Cuboid,
+                    size: Vec3::splat(0.05),
+                    material: BlockMaterial::Wood,
+                },
+                Transform::from_translation(block_at),
+            );
+        },
+    );
+    app
+}
+
+#[test]
+fn pinched_block_is_carried_and_thrown() {
+    let start = Vec3::new(0.0, 1.5, -0.3);
+    let carried = start + Vec3::new(0.2, 0.0, 0.0);
+    // Keep moving at the same speed while letting go
+    let thrown = carried + Vec3::new(0.16, 0.0, 0.0);
+    let speed = 0.2 / 0.5;
+
+    let mut app = grab_app(pinch_point(start));
+    app.insert_resource(SimulatedHands {
+        left: Trajectory::new(HandPose::open(Vec3::new(-0.5, 1.5, -0.3))),
+        right: Trajectory::new(HandPose::open(start))
+            .then(0.1, HandPose::pinching(start))
+            .hold(0.2)
+            .then(0.5, HandPose::pinching(carried))
+            .then(0.4, HandPose::open(thrown)),
+    });
+
+    run_for(&mut app, 0.45);
+    let world = &mut app.world;
+    let (body, grabbed) = world
+        .query_filtered::<(&RigidBody, Option<&Grabbed>), With<Block>>()
+        .single(world);
+    assert!(matches!(body, RigidBody::Kinematic));
+    assert!(grabbed.is_some());
+
+    // Halfway through the carry the block follows the pinch point
+    run_for(&mut app, 0.1);
+    let world = &mut app.world;
+    let position = world
+        .query_filtered::<&Position, With<Block>>()
+        .single(world)
+        .0;
+    let halfway = pinch_point(start.lerp(carried, 0.5));
+    assert!(position.distance(halfway) < 0.03, "{position} vs {halfway}");
+
+    run_for(&mut app, 0.8);
+    let world = &mut app.world;
+    let (body, grabbed, velocity) = world
+        .query_filtered::<(&RigidBody, Option<&Grabbed>, &LinearVelocity), With<Block>>()
+        .single(world);
+    assert!(matches!(body, RigidBody::Dynamic));
+    assert!(grabbed.is_none());
+    // Thrown along the hand movement at about the hand's speed
+    assert!(
+        velocity.x > speed * 0.7 && velocity.x < speed * 1.3,
+        "{velocity:?}"
+    );
+    assert!(velocity.y.abs() < speed * 0.3 && velocity.z.abs() < speed * 0.3);
+}
+
+#[test]
+fn held_block_stops_while_the_hand_is_lost() {
+    let start = Vec3::new(0.0, 1.5, -0.3);
+    let mut app = grab_app(pinch_point(start));
+    app.insert_resource(SimulatedHands {
+        left: Trajectory::new(HandPose::open(Vec3::new(-0.5, 1.5, -0.3))),
+        right: Trajectory::new(HandPose::open(start))
+            .then(0.1, HandPose::pinching(start))
+            .hold(0.2)
+            .then(0.5, HandPose::pinching(start + Vec3::new(0.2, 0.0, 0.0))),
+    });
+    run_for(&mut app, 0.5);
+
+    // Tracking drops out mid carry, the block stays where it was instead of drifting off
+    app.world.resource_mut::<SimulatedHands>().right = Trajectory::default();
+    run_for(&mut app, 0.1);
+    let position = |app: &mut App| {
+        let world = &mut app.world;
+        world
+            .query_filtered::<&Position, With<Block>>()
+            .single(world)
+            .0
+    };
+    let lost_at = position(&mut app);
+    run_for(&mut app, 0.5);
+    assert!(position(&mut app).distance(lost_at) < 1e-4);
+    let world = &mut app.world;
+    let (grabbed, velocity) = world
+        .query_filtered::<(Option<&Grabbed>, &LinearVelocity), With<Block>>()
+        .single(world);
+    assert!(grabbed.is_some());
+    assert_eq!(velocity.0, Vec3::ZERO);
+}
@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::deletion::DeletionSettings;
+use bevy_vr_blocks::gizmo_text::text_width;
+use bevy_vr_blocks::hand_menu::{
+    HandMenu, HandMenuPlugin, HandMenuSettings, MenuAction, MenuButton,
+};
+use bevy_vr_blocks::test_support::{
+    headless_app, run_for, HandPose, SimulatedHands, SimulatedHandsPlugin, Trajectory,
+};
+use bevy_vr_blocks::widgets::{Toggle, ToggleChanged, WidgetSet, WidgetSettings};
+
+#[test]
+fn labels_fit_their_buttons() {
+    let settings = HandMenuSettings::default();
+    let text_height = WidgetSettings::default().text_height;
+    for action in MenuAction::layout().into_iter().flatten() {
+        let width = text_width(&action.label(), text_height);
+        assert!(width < settings.button_size.x, "{action:?} is too wide");
+    }
+}
+
+#[test]
+fn upturned_palm_opens_the_menu() {
+    let eye = Vec3::new(0.0, 1.5, 0.0);
+    let palm = Vec3::new(0.0, 1.1, -0.3);
+    let to_eye = (eye - palm).normalize();
+    let facing = HandPose {
+        palm: Transform::from_translation(palm)
+            .with_rotation(Quat::from_rotation_arc(Vec3::Y, -to_eye)),
+        ..HandPose::open(palm + Vec3::new(0.0, 0.0, -0.08))
+    };
+    let turned_away = HandPose {
+        palm: Transform::from_translation(palm),
+        ..facing
+    };
+
+    let mut app = headless_app();
+    app.add_plugins((SimulatedHandsPlugin, HandMenuPlugin));
+    app.world.spawn((
+        Camera3d::default(),
+        TransformBundle::from_transform(Transform::from_translation(eye).looking_at(palm, Vec3::Y)),
+    ));
+    app.insert_resource(SimulatedHands {
+        left: Trajectory::new(turned_away)
+            .hold(0.5)
+            .then(0.0, facing)
+            .hold(0.5),
+        right: Trajectory::new(HandPose::open(Vec3::new(0.3, 1.0, -0.3))),
+    });
+
+    let open = |app: &mut App| app.world.query::<&HandMenu>().single(&app.world).open;
+    run_for(&mut app, 0.3);
+    assert!(!open(&mut app));
+    run_for(&mut app, 0.5);
+    assert!(open(&mut app));
+}
+
+/// An open left hand with the palm at `palm` facing `eye`.
+fn facing(palm: Vec3, eye: Vec3) -> HandPose {
+    let to_eye = (eye - palm).normalize();
+    HandPose {
+        palm: Transform::from_translation(palm)
+            .with_rotation(Quat::from_rotation_arc(Vec3::Y, -to_eye)),
+        ..HandPose::open(palm + Vec3::new(0.0, 0.0, -0.08))
+    }
+}
+
+/// How far the menu widgets are from where the menu is when they get poked.
+#[derive(Resource, Default)]
+struct WidgetLag(f32);
+
+fn record_widget_lag(
+    menus: Query<(&HandMenu, &Transform, &Children)>,
+    widgets: Query<(&Transform, &GlobalTransform), With<MenuButton>>,
+    mut lag: ResMut<WidgetLag>,
+) {
+    for (menu, menu_transform, children) in &menus {
+        if !menu.open {
+            continue;
+        }
+        for (transform, global) in widgets.iter_many(children) {
+            let expected = (*menu_transform * *transform).translation;
+            lag.0 = lag.0.max(global.translation().distance(expected));
+        }
+    }
+}
+
+#[test]
+fn menu_widgets_follow_the_palm_in_the_same_frame() {
+    let eye = Vec3::new(0.0, 1.5, 0.0);
+    let start = Vec3::new(0.0, 1.1, -0.3);
+    let end = Vec3::new(0.15, 1.15, -0.3);
+
+    let mut app = headless_app();
+    app.add_plugins((SimulatedHandsPlugin, HandMenuPlugin));
+    app.world.spawn((
+        Camera3d::default(),
+        TransformBundle::from_transform(
+            Transform::from_translation(eye).looking_at(start, Vec3::Y),
+        ),
+    ));
+    app.insert_resource(SimulatedHands {
+        left: Trajectory::new(facing(start, eye))
+            .hold(0.3)
+            .then(0.5, facing(end, eye)),
+        right: Trajectory::new(HandPose::open(Vec3::new(0.3, 1.0, -0.3))),
+    });
+    app.init_resource::<WidgetLag>();
+    app.add_systems(Update, record_widget_lag.in_set(WidgetSet));
+
+    run_for(&mut app, 0.8);
+    assert!(app.world.query::<&HandMenu>().single(&app.world).open);
+    let lag = app.world.resource::<WidgetLag>().0;
+    assert!(lag < 1e-4, "widgets lag {lag} m behind the menu");
+}
+
+#[test]
+fn poking_a_menu_toggle_switches_its_tool() {
+    let eye = Vec3::new(0.0, 1.5, 0.0);
+    let palm = Vec3::new(0.0, 1.1, -0.3);
+    // Off to the side and in front of the menu, so the finger only ever reaches it from the front
+    let away = Vec3::new(0.5, 1.0, 0.0);
+
+    let mut app = headless_app();
+    app.add_plugins((SimulatedHandsPlugin, HandMenuPlugin));
+    app.init_resource::<DeletionSettings>();
+    app.world.spawn((
+        Camera3d::default(),
+        TransformBundle::from_transform(Transform::from_translation(eye).looking_at(palm, Vec3::Y)),
+    ));
+    app.insert_resource(SimulatedHands {
+        left: Trajectory::new(facing(palm, eye)),
+        right: Trajectory::new(HandPose::open(away)),
+    });
+    run_for(&mut app, 0.3);
+    assert!(app.world.query::<&HandMenu>().single(&app.world).open);
+
+    // Push the right index tip into the delete toggle and pull it back out
+    let world = &mut app.world;
+    let delete = world
+        .query::<(&MenuButton, &GlobalTransform)>()
+        .iter(world)
+        .find(|(button, _)| button.0 == MenuAction::Delete)
+        .map(|(_, transform)| *transform)
+        .expect("the menu should have a delete toggle");
+    let in_front = HandPose::open(delete.transform_point(Vec3::new(0.0, 0.0, 0.02)));
+    let pushed = HandPose::open(delete.transform_point(Vec3::new(0.0, 0.0, -0.008)));
+    let now = app.world.resource::<Time>().elapsed_seconds();
+    app.world.resource_mut::<SimulatedHands>().right = Trajectory::new(HandPose::open(away))
+        .hold(now)
+        .then(0.2, in_front)
+        .then(0.2, pushed)
+        .then(0.2, in_front);
+    run_for(&mut app, 0.8);
+
+    assert!(!app.world.resource::<DeletionSettings>().enabled);
+    let world = &mut app.world;
+    let toggle = world
+        .query::<(&MenuButton, &Toggle)>()
+        .iter(world)
+        .find(|(button, _)| button.0 == MenuAction::Delete)
+        .map(|(_, toggle)| *toggle);
+    assert_eq!(toggle, Some(Toggle(false)));
+}
+
+#[test]
+fn tool_toggles_switch_tools_on_and_off() {
+    let mut app = headless_app();
+    app.add_plugins(HandMenuPlugin);
+    app.init_resource::<DeletionSettings>();
+    app.update();
+    let world = &mut app.world;
+    let (delete, _) = world
+        .query::<(Entity, &MenuButton)>()
+        .iter(world)
+        .find(|(_, button)| button.0 == MenuAction::Delete)
+        .expect("the menu should have a delete toggle");
+
+    world.send_event(ToggleChanged {
+        entity: delete,
+        on: false,
+    });
+    app.update();
+    assert!(!app.world.resource::<DeletionSettings>().enabled);
+    app.update();
+    assert_eq!(app.world.get::<Toggle>(delete), Some(&Toggle(false)));
+}
@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::hand_physics::{HandCollider, HandPhysicsPlugin};
+use bevy_vr_blocks::test_support::{headless_app, run_for};
+use bevy_xpbd_3d::prelude::*;
+use bevy_xr::hands::{HandBone, HandBoneRadius, RightHand};
+
+#[derive(Resource)]
+struct MovingTip(Entity);
+
+/// Moves the tip along X at one meter per second.
+fn move_tip(time: Res<Time>, tip: Res<MovingTip>, mut bones: Query<&mut GlobalTransform>) {
+    let Ok(mut transform) = bones.get_mut(tip.0) else {
+        return;
+    };
+    let translation = Vec3::new(time.elapsed_seconds(), 1.4, -0.3);
+    *transform = GlobalTransform::from_translation(translation);
+}
+
+#[test]
+fn collider_stays_put_when_tracking_is_lost() {
+    let mut app = headless_app();
+    app.add_plugins(HandPhysicsPlugin);
+    let tip = app
+        .world
+        .spawn((
+            HandBone::IndexTip,
+            HandBoneRadius(0.01),
+            RightHand,
+            SpatialBundle::default(),
+        ))
+        .id();
+    app.insert_resource(MovingTip(tip));
+    app.add_systems(PreUpdate, move_tip);
+    run_for(&mut app, 0.3);
+
+    let world = &mut app.world;
+    let (_, linear) = world
+        .query::<(&HandCollider, &LinearVelocity)>()
+        .single(world);
+    assert!(linear.x > 0.5, "{linear:?}");
+
+    // The runtime drops the bones of a hand it loses
+    app.world.despawn(tip);
+    app.update();
+    let world = &mut app.world;
+    let lost_at = world
+        .query::<(&HandCollider, &Position)>()
+        .single(world)
+        .1
+         .0;
+    run_for(&mut app, 0.5);
+    let world = &mut app.world;
+    let (_, position, linear) = world
+        .query::<(&HandCollider, &Position, &LinearVelocity)>()
+        .single(world);
+    assert_eq!(linear.0, Vec3::ZERO);
+    assert!(
+        position.0.distance(lost_at) < 1e-3,
+        "{} vs {lost_at}",
+        position.0
+    );
+}
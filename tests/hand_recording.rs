@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::cube_creation::CubeCreationPlugin;
+use bevy_vr_blocks::hand_recording::{HandRecording, HandReplay, HandReplayPlugin};
+use bevy_vr_blocks::test_support::{headless_app, run_for, SimulatedHands};
+use bevy_xpbd_3d::prelude::*;
+
+fn pinch_session() -> SimulatedHands {
+    let start = Vec3::new(0.0, 1.5, -0.3);
+    let left_end = Vec3::new(-0.15, 1.45, -0.3);
+    let right_end = Vec3::new(0.15, 1.55, -0.3);
+    SimulatedHands::pinch_stretch(start, left_end, right_end)
+}
+
+#[test]
+fn recording_round_trips() {
+    let recording = pinch_session().to_recording();
+    let mut bytes = Vec::new();
+    recording.write(&mut bytes).unwrap();
+    assert_eq!(HandRecording::read(bytes.as_slice()).unwrap(), recording);
+}
+
+#[test]
+fn truncated_recording_is_an_error() {
+    let recording = pinch_session().to_recording();
+    let mut bytes = Vec::new();
+    recording.write(&mut bytes).unwrap();
+    // Cut the last byte off the last frame, then cut frames off partway through their time
+    bytes.truncate(bytes.len() - 1);
+    assert!(HandRecording::read(bytes.as_slice()).is_err());
+    for cut in 1..4 {
+        let mut bytes = Vec::new();
+        HandRecording {
+            frames: recording.frames[..1].to_vec(),
+        }
+        .write(&mut bytes)
+        .unwrap();
+        bytes.extend_from_slice(&1.0f32.to_le_bytes()[..cut]);
+        assert!(HandRecording::read(bytes.as_slice()).is_err());
+    }
+}
+
+#[test]
+fn missing_replay_file_is_logged_not_fatal() {
+    let mut app = headless_app();
+    app.add_plugins(HandReplayPlugin::from_file("does/not/exist.vrbh"));
+    run_for(&mut app, 0.1);
+    assert!(app.world.resource::<HandReplay>().finished());
+}
+
+#[test]
+fn replayed_pinch_session_spawns_one_cube() {
+    let mut app = headless_app();
+    app.add_plugins((
+        HandReplayPlugin {
+            recording: pinch_session().to_recording(),
+            path: None,
+            looping: false,
+        },
+        CubeCreationPlugin,
+    ));
+
+    while !app.world.resource::<HandReplay>().finished() {
+        app.update();
+    }
+    run_for(&mut app, 0.2);
+
+    let world = &mut app.world;
+    let mut bodies = world.query::<&RigidBody>();
+    let cubes = bodies
+        .iter(world)
+        .filter(|body| matches!(body, RigidBody::Dynamic))
+        .count();
+    assert_eq!(cubes, 1);
+}
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::hands::{HandState, HandsPlugin, HandsState};
+use bevy_vr_blocks::test_support::{
+    headless_app, run_for, HandPose, SimulatedHands, SimulatedHandsPlugin, Trajectory,
+};
+use bevy_xr::hands::HandBone;
+
+#[test]
+fn hands_state_follows_the_bones() {
+    let left_tip = Vec3::new(-0.1, 1.4, -0.3);
+    let right_tip = Vec3::new(0.1, 1.4, -0.3);
+    let mut app = headless_app();
+    app.add_plugins((SimulatedHandsPlugin, HandsPlugin));
+    app.insert_resource(SimulatedHands {
+        left: Trajectory::new(HandPose::pinching(left_tip)),
+        right: Trajectory::new(HandPose::open(right_tip)),
+    });
+    run_for(&mut app, 0.3);
+
+    let hands = app.world.resource::<HandsState>();
+    assert!(hands.left.tracked && hands.right.tracked);
+    assert!(hands.left.pinching);
+    assert!(!hands.right.pinching);
+    assert_eq!(hands.left.pinch_strength, 1.0);
+    assert_eq!(hands.right.pinch_strength, 0.0);
+    let index_tip = hands.right.joint(HandBone::IndexTip).unwrap();
+    assert!(index_tip.translation.distance(right_tip) < 1e-5);
+    assert!(hands.left.palm_normal.unwrap().distance(Vec3::NEG_Y) < 1e-5);
+    assert!(hands.left.joint(HandBone::IndexDistal).is_none());
+    let pinch_point = hands.left.pinch_point.unwrap().translation;
+    assert!(pinch_point.distance(left_tip - Vec3::new(0.0, 0.005, 0.0)) < 1e-5);
+}
+
+#[test]
+fn hands_the_runtime_stops_moving_are_not_tracked() {
+    let mut app = headless_app();
+    app.add_plugins((SimulatedHandsPlugin, HandsPlugin));
+    app.insert_resource(SimulatedHands {
+        left: Trajectory::new(HandPose::pinching(Vec3::new(-0.1, 1.4, -0.3))),
+        right: Trajectory::new(HandPose::open(Vec3::new(0.1, 1.4, -0.3))),
+    });
+    run_for(&mut app, 0.3);
+    assert!(app.world.resource::<HandsState>().left.pinching);
+
+    // Tracking of the left hand is lost, its bones stay where they were
+    app.world.resource_mut::<SimulatedHands>().left = Trajectory::default();
+    run_for(&mut app, 0.05);
+    let hands = app.world.resource::<HandsState>();
+    assert!(hands.right.tracked);
+    assert_eq!(hands.left, HandState::default());
+}
@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::block::{
+    spawn_block, Block, BlockId, BlockMoved, BlockRepainted, BlockSpawned,
+};
+use bevy_vr_blocks::block_material::BlockMaterial;
+use bevy_vr_blocks::history::{BlockHistory, HistoryPlugin, HistoryStep};
+use bevy_vr_blocks::shapes::ShapeKind;
+use bevy_vr_blocks::test_support::{headless_app, run_for};
+use bevy_xr::hands::{HandBone, LeftHand};
+
+fn block_count(app: &mut App) -> usize {
+    app.world.query::<&Block>().iter(&app.world).count()
+}
+
+/// Spawns a block at startup, recording it in the history.
+fn add_block(app: &mut App) {
+    app.add_systems(
+        Startup,
+        |mut commands: Commands,
+         mut meshes: ResMut<Assets<Mesh>>,
+         mut materials: ResMut<Assets<StandardMaterial>>,
+         mut spawned: EventWriter<BlockSpawned>| {
+            let block = spawn_block(
+                &mut commands,
+                &mut meshes,
+                materials.add(Color::RED),
+                Block {
+                    shape: ShapeKind::Cuboid,
+                    size: Vec3::splat(0.1),
+                    material: BlockMaterial::Rubber,
+                },
+                Transform::from_xyz(0.0, 1.5, 0.0),
+            );
+            spawned.send(BlockSpawned(block));
+        },
+    );
+}
+
+#[test]
+fn undo_and_redo_spawned_block() {
+    let mut app = headless_app();
+    app.add_plugins(HistoryPlugin);
+    add_block(&mut app);
+    app.update();
+    assert_eq!(block_count(&mut app), 1);
+    assert!(app.world.resource::<BlockHistory>().can_undo());
+
+    app.world.send_event(HistoryStep::Undo);
+    app.update();
+    assert_eq!(block_count(&mut app), 0);
+    assert!(app.world.resource::<BlockHistory>().can_redo());
+
+    app.world.send_event(HistoryStep::Redo);
+    app.update();
+    assert_eq!(block_count(&mut app), 1);
+    assert!(!app.world.resource::<BlockHistory>().can_redo());
+}
+
+#[test]
+fn steps_are_applied_in_the_order_they_were_sent() {
+    let mut app = headless_app();
+    app.add_plugins(HistoryPlugin);
+    add_block(&mut app);
+    app.update();
+    let world = &mut app.world;
+    let id = *world.query::<&BlockId>().single(world);
+    let moved = Transform::from_xyz(0.3, 1.2, -0.2);
+    world.send_event(BlockMoved {
+        id,
+        from: Transform::from_xyz(0.0, 1.5, 0.0),
+        to: moved,
+    });
+    app.update();
+
+    // Redoing the respawn has to happen before the move can be redone on the respawned block
+    for step in [
+        HistoryStep::Undo,
+        HistoryStep::Undo,
+        HistoryStep::Redo,
+        HistoryStep::Redo,
+    ] {
+        app.world.send_event(step);
+    }
+    app.update();
+    let world = &mut app.world;
+    let transform = *world
+        .query_filtered::<&Transform, With<Block>>()
+        .single(world);
+    assert!(
+        transform.translation.distance(moved.translation) < 1e-4,
+        "{transform:?}"
+    );
+    assert!(!world.resource::<BlockHistory>().can_redo());
+}
+
+#[test]
+fn undo_and_redo_repainted_block() {
+    let mut app = headless_app();
+    app.add_plugins(HistoryPlugin);
+    add_block(&mut app);
+    app.update();
+    let world = &mut app.world;
+    let blue = world
+        .resource_mut::<Assets<StandardMaterial>>()
+        .add(Color::BLUE);
+    let (id, mut material) = world
+        .query::<(&BlockId, &mut Handle<StandardMaterial>)>()
+        .single_mut(world);
+    let (id, red) = (*id, std::mem::replace(&mut *material, blue.clone()));
+    world.send_event(BlockRepainted {
+        id,
+        from: red.clone(),
+        to: blue.clone(),
+    });
+    app.update();
+
+    let material = |app: &mut App| {
+        let world = &mut app.world;
+        world
+            .query::<&Handle<StandardMaterial>>()
+            .single(world)
+            .clone()
+    };
+    app.world.send_event(HistoryStep::Undo);
+    app.update();
+    assert_eq!(material(&mut app), red);
+    // The block is still there, only its paint was undone
+    assert!(app.world.resource::<BlockHistory>().can_undo());
+
+    app.world.send_event(HistoryStep::Redo);
+    app.update();
+    assert_eq!(material(&mut app), blue);
+}
+
+#[derive(Resource, Default)]
+struct SentSteps(Vec<HistoryStep>);
+
+/// An app with a left thumb, index and middle tip, and a count of the steps sent.
+fn gesture_app() -> (App, [Entity; 3]) {
+    let mut app = headless_app();
+    app.add_plugins(HistoryPlugin);
+    app.init_resource::<SentSteps>();
+    app.add_systems(
+        Update,
+        |mut steps: EventReader<HistoryStep>, mut sent: ResMut<SentSteps>| {
+            sent.0.extend(steps.read().copied());
+        },
+    );
+    // A runtime writes the bones of a tracked hand every frame, even when it holds still
+    app.add_systems(
+        PreUpdate,
+        |mut bones: Query<&mut Transform, With<HandBone>>| {
+            bones.iter_mut().for_each(|mut bone| bone.set_changed());
+        },
+    );
+    let bones = [HandBone::ThumbTip, HandBone::IndexTip, HandBone::MiddleTip].map(|bone| {
+        app.world
+            .spawn((bone, LeftHand, SpatialBundle::default()))
+            .id()
+    });
+    (app, bones)
+}
+
+/// Places the thumb, index and middle tip of the gesture app's hand.
+fn place(app: &mut App, bones: [Entity; 3], tips: [Vec3; 3]) {
+    for (entity, tip) in bones.into_iter().zip(tips) {
+        *app.world.get_mut::<Transform>(entity).unwrap() = Transform::from_translation(tip);
+    }
+}
+
+const MIDDLE: Vec3 = Vec3::new(0.0, 1.4, -0.3);
+const STRETCHED_INDEX: Vec3 = Vec3::new(-0.02, 1.48, -0.3);
+const CURLED_INDEX: Vec3 = Vec3::new(-0.02, 1.4, -0.3);
+const APART: Vec3 = Vec3::new(0.0, 1.3, -0.3);
+
+#[test]
+fn held_gesture_undoes_once() {
+    let (mut app, bones) = gesture_app();
+    place(&mut app, bones, [APART, STRETCHED_INDEX, MIDDLE]);
+    run_for(&mut app, 0.1);
+    place(&mut app, bones, [MIDDLE, STRETCHED_INDEX, MIDDLE]);
+    run_for(&mut app, 0.5);
+    assert_eq!(app.world.resource::<SentSteps>().0, [HistoryStep::Undo]);
+
+    // Opening the fingers again arms the next step
+    place(&mut app, bones, [APART, STRETCHED_INDEX, MIDDLE]);
+    run_for(&mut app, 0.1);
+    place(&mut app, bones, [MIDDLE, STRETCHED_INDEX, MIDDLE]);
+    run_for(&mut app, 0.5);
+    assert_eq!(app.world.resource::<SentSteps>().0.len(), 2);
+}
+
+#[test]
+fn brief_touch_and_fist_do_not_undo() {
+    let (mut app, bones) = gesture_app();
+    place(&mut app, bones, [APART, STRETCHED_INDEX, MIDDLE]);
+    run_for(&mut app, 0.1);
+    // Shorter than the dwell
+    place(&mut app, bones, [MIDDLE, STRETCHED_INDEX, MIDDLE]);
+    run_for(&mut app, 0.05);
+    place(&mut app, bones, [APART, STRETCHED_INDEX, MIDDLE]);
+    run_for(&mut app, 0.1);
+    // A fist brings the thumb to the middle tip with the index curled next to it
+    place(&mut app, bones, [MIDDLE, CURLED_INDEX, MIDDLE]);
+    run_for(&mut app, 0.5);
+    assert!(app.world.resource::<SentSteps>().0.is_empty());
+}
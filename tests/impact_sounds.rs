@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::block::{spawn_block, Block};
+use bevy_vr_blocks::block_material::BlockMaterial;
+use bevy_vr_blocks::impact_sounds::{ImpactSoundPlugin, ImpactSoundSettings, ImpactVoice};
+use bevy_vr_blocks::layers::Layer;
+use bevy_vr_blocks::shapes::ShapeKind;
+use bevy_vr_blocks::test_support::{headless_app, run_for};
+use bevy_vr_blocks::Floor;
+use bevy_xpbd_3d::prelude::*;
+
+fn voice_count(app: &mut App) -> usize {
+    app.world.query::<&ImpactVoice>().iter(&app.world).count()
+}
+
+fn spawn_floor(app: &mut App) {
+    app.world.spawn((
+        Floor,
+        RigidBody::Static,
+        Collider::cuboid(2.0, 0.002, 2.0),
+        Layer::Floor.collision_layers(),
+        TransformBundle::from_transform(Transform::from_xyz(0.0, 1.0, 0.0)),
+    ));
+}
+
+/// Adds a block at startup, `height` meters above the floor.
+fn add_block(app: &mut App, height: f32) {
+    app.add_systems(
+        Startup,
+        move |mut commands: Commands,
+              mut meshes: ResMut<Assets<Mesh>>,
+              mut materials: ResMut<Assets<StandardMaterial>>| {
+            spawn_block(
+                &mut commands,
+                &mut meshes,
+                materials.add(Color::RED),
+                Block {
+                    shape: ShapeKind::Cuboid,
+                    size: Vec3::splat(0.1),
+                    material: BlockMaterial::Wood,
+                },
+                Transform::from_xyz(0.0, 1.001 + 0.05 + height, 0.0),
+            );
+        },
+    );
+}
+
+#[test]
+fn landing_block_sounds_once_then_rests_silently() {
+    let mut app = headless_app();
+    app.add_plugins(ImpactSoundPlugin);
+    spawn_floor(&mut app);
+    add_block(&mut app, 0.2);
+
+    run_for(&mut app, 1.5);
+    let landed = voice_count(&mut app);
+    assert!(landed >= 1);
+
+    // Lying on the floor keeps touching it every frame, long past the pair cooldown
+    run_for(&mut app, 2.0);
+    assert_eq!(voice_count(&mut app), landed);
+}
+
+/// Runs a block landing with the voice pool full of two voices of `energy`, returning the
+/// energies of the voices afterwards.
+fn land_with_full_pool(energy: f32) -> Vec<f32> {
+    let mut app = headless_app();
+    app.add_plugins(ImpactSoundPlugin);
+    app.insert_resource(ImpactSoundSettings {
+        max_voices: 2,
+        ..default()
+    });
+    spawn_floor(&mut app);
+    add_block(&mut app, 0.1);
+    for _ in 0..2 {
+        app.world.spawn(ImpactVoice {
+            energy,
+            started: 0.0,
+        });
+    }
+
+    // Just past the first landing at about 0.14 seconds, before the first bounce lands again
+    run_for(&mut app, 0.2);
+    let world = &mut app.world;
+    world
+        .query::<&ImpactVoice>()
+        .iter(world)
+        .map(|voice| voice.energy)
+        .collect()
+}
+
+#[test]
+fn harder_hit_takes_over_the_weakest_voice() {
+    let energies = land_with_full_pool(1e-6);
+    assert_eq!(energies.len(), 2);
+    assert!(energies.contains(&1e-6));
+    assert!(energies.iter().any(|energy| *energy > 0.1), "{energies:?}");
+}
+
+#[test]
+fn weaker_hit_does_not_cut_off_louder_voices() {
+    let energies = land_with_full_pool(1e6);
+    assert_eq!(energies, [1e6, 1e6]);
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_vr_blocks::layers::Layer;
+use bevy_vr_blocks::test_support::headless_app;
+use bevy_vr_blocks::widgets::{Panel, PokeButton, Widget, WidgetPlugin};
+use bevy_xpbd_3d::prelude::*;
+
+#[test]
+fn layers_filter_the_right_pairs() {
+    let interacts = |a: Layer, b: Layer| a.collision_layers().interacts_with(b.collision_layers());
+    assert!(interacts(Layer::Hands, Layer::Blocks));
+    assert!(!interacts(Layer::Hands, Layer::Hands));
+    assert!(!interacts(Layer::Hands, Layer::Held));
+    assert!(!interacts(Layer::Hands, Layer::Floor));
+    assert!(interacts(Layer::Held, Layer::Blocks));
+    assert!(interacts(Layer::Blocks, Layer::Floor));
+    for layer in [
+        Layer::Hands,
+        Layer::Blocks,
+        Layer::Floor,
+        Layer::Held,
+        Layer::Ui,
+    ] {
+        assert!(!interacts(Layer::Ui, layer), "{layer:?}");
+    }
+}
+
+#[test]
+fn widgets_and_panels_get_ui_colliders() {
+    let mut app = headless_app();
+    app.add_plugins(WidgetPlugin);
+    let panel = app
+        .world
+        .spawn((
+            Panel::new(Vec2::new(0.1, 0.1), "Panel"),
+            SpatialBundle::default(),
+        ))
+        .id();
+    let button = app
+        .world
+        .spawn((
+            PokeButton,
+            Widget::new(Vec2::new(0.04, 0.02), "Poke"),
+            SpatialBundle::default(),
+        ))
+        .id();
+    app.update();
+
+    for entity in [panel, button] {
+        let entity = app.world.entity(entity);
+        assert!(entity.contains::<Collider>());
+        assert_eq!(
+            entity.get::<CollisionLayers>(),
+            Some(&Layer::Ui.collision_layers())
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+struct Touching(HashSet<(Entity, Entity)>);
+
+#[test]
+fn hand_colliders_touch_blocks_but_not_each_other() {
+    let mut app = headless_app();
+    app.insert_resource(Gravity(Vec3::ZERO));
+    app.init_resource::<Touching>();
+    app.add_systems(
+        PostUpdate,
+        (|mut collisions: EventReader<Collision>, mut touching: ResMut<Touching>| {
+            for Collision(contacts) in collisions.read() {
+                let pair = (
+                    contacts.entity1.min(contacts.entity2),
+                    contacts.entity1.max(contacts.entity2),
+                );
+                touching.0.insert(pair);
+            }
+        })
+        .after(PhysicsSet::StepSimulation),
+    );
+    // Dynamic bodies, so only the layers keep the hands apart
+    let mut spawn = |layer: Layer, x: f32| {
+        app.world
+            .spawn((
+                RigidBody::Dynamic,
+                Collider::sphere(0.02),
+                layer.collision_layers(),
+                TransformBundle::from_transform(Transform::from_xyz(x, 1.5, 0.0)),
+            ))
+            .id()
+    };
+    let hand1 = spawn(Layer::Hands, 0.0);
+    let hand2 = spawn(Layer::Hands, 0.01);
+    let block = spawn(Layer::Blocks, 0.03);
+    app.update();
+    app.update();
+
+    let touching = &app.world.resource::<Touching>().0;
+    let pair = |a: Entity, b: Entity| (a.min(b), a.max(b));
+    assert!(touching.contains(&pair(hand2, block)));
+    assert!(!touching.contains(&pair(hand1, hand2)));
+}
@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::palette::{
+    hsv, BlockTexture, Paint, PalettePlugin, PaletteTextures, SelectedPaint, Swatch, ValueSlider,
+};
+use bevy_vr_blocks::test_support::{
+    headless_app, run_for, HandPose, SimulatedHands, SimulatedHandsPlugin, Trajectory,
+};
+use bevy_vr_blocks::widgets::SliderChanged;
+
+fn assert_close(a: Color, b: Color) {
+    let (a, b) = (Vec4::from(a.as_rgba_f32()), Vec4::from(b.as_rgba_f32()));
+    assert!(a.abs_diff_eq(b, 1e-4), "{a} != {b}");
+}
+
+#[test]
+fn hsv_matches_rgb() {
+    assert_close(hsv(0.0, 1.0, 1.0), Color::rgb(1.0, 0.0, 0.0));
+    assert_close(hsv(120.0, 1.0, 0.5), Color::rgb(0.0, 0.5, 0.0));
+    assert_close(hsv(240.0, 0.5, 1.0), Color::rgb(0.5, 0.5, 1.0));
+    assert_close(hsv(60.0, 0.0, 0.25), Color::rgb(0.25, 0.25, 0.25));
+    assert_close(hsv(300.0, 1.0, 0.0), Color::BLACK);
+}
+
+#[test]
+fn textures_are_recognized_from_their_images() {
+    let mut app = headless_app();
+    app.init_resource::<PaletteTextures>();
+    let textures = app.world.resource::<PaletteTextures>();
+    for texture in BlockTexture::ALL {
+        let image = textures.image(texture);
+        assert_eq!(image.is_none(), texture == BlockTexture::Plain);
+        assert_eq!(textures.texture_of(image.as_ref()), texture);
+    }
+}
+
+#[test]
+fn value_slider_darkens_the_wheel_and_the_selected_color() {
+    let mut app = headless_app();
+    app.add_plugins(PalettePlugin);
+    app.update();
+    app.world.resource_mut::<SelectedPaint>().paint = Paint::Color(hsv(120.0, 1.0, 1.0));
+    let world = &mut app.world;
+    let slider = world
+        .query_filtered::<Entity, With<ValueSlider>>()
+        .single(world);
+    world.send_event(SliderChanged {
+        entity: slider,
+        value: 0.5,
+    });
+    app.update();
+
+    let selected = *app.world.resource::<SelectedPaint>();
+    assert_eq!(selected.value, 0.5);
+    assert_eq!(selected.paint, Paint::Color(hsv(120.0, 1.0, 0.5)));
+    let world = &mut app.world;
+    let mut swatches = world.query::<(&Swatch, &Handle<StandardMaterial>)>();
+    let (_, green) = swatches
+        .iter(world)
+        .find(|(swatch, _)| {
+            **swatch
+                == Swatch::Wheel {
+                    hue: 120.0,
+                    saturation: 1.0,
+                }
+        })
+        .expect("the wheel should have a green swatch");
+    let green = world
+        .resource::<Assets<StandardMaterial>>()
+        .get(green)
+        .unwrap();
+    assert_close(green.base_color, hsv(120.0, 1.0, 0.5));
+}
+
+#[test]
+fn dragging_the_value_slider_sets_the_value() {
+    let mut app = headless_app();
+    app.add_plugins((SimulatedHandsPlugin, PalettePlugin));
+    app.update();
+    let world = &mut app.world;
+    let slider = *world
+        .query_filtered::<&GlobalTransform, With<ValueSlider>>()
+        .single(world);
+    let tip = |x: f32, z: f32| HandPose::open(slider.transform_point(Vec3::new(x, 0.0, z)));
+
+    // Push in near the bright end, drag a quarter of the way from the dark end and let go
+    let now = app.world.resource::<Time>().elapsed_seconds();
+    app.world.resource_mut::<SimulatedHands>().right = Trajectory::new(tip(0.05, 0.1))
+        .hold(now)
+        .then(0.1, tip(0.05, 0.02))
+        .then(0.2, tip(0.05, -0.008))
+        .then(0.3, tip(-0.03, -0.008))
+        .then(0.2, tip(-0.03, 0.05));
+    run_for(&mut app, 1.0);
+
+    let value = app.world.resource::<SelectedPaint>().value;
+    assert!((value - 0.25).abs() < 1e-4, "value is {value}");
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy_vr_blocks::block::Block;
+use bevy_vr_blocks::block_material::BlockMaterial;
+use bevy_vr_blocks::palette::BlockTexture;
+use bevy_vr_blocks::persistence::{
+    BlockPersistencePlugin, BlockPersistenceSettings, BlockScene, LoadBlocks, SavedBlock,
+};
+use bevy_vr_blocks::shapes::ShapeKind;
+use bevy_vr_blocks::test_support::headless_app;
+
+fn scene() -> BlockScene {
+    BlockScene {
+        blocks: vec![
+            SavedBlock {
+                shape: ShapeKind::Cuboid,
+                size: [0.1, 0.2, 0.3],
+                material: BlockMaterial::Wood,
+                texture: BlockTexture::Plain,
+                translation: [0.0, 1.2, -0.4],
+                rotation: Quat::from_rotation_y(0.3).to_array(),
+                color: [1.0, 0.5, 0.0, 1.0],
+                linear_velocity: [0.0; 3],
+                angular_velocity: [0.0; 3],
+            },
+            SavedBlock {
+                shape: ShapeKind::Wedge,
+                size: [0.3, 0.1, 0.3],
+                material: BlockMaterial::Metal,
+                texture: BlockTexture::Checker,
+                translation: [0.2, 1.1, -0.4],
+                rotation: Quat::IDENTITY.to_array(),
+                color: [0.2, 0.4, 0.8, 1.0],
+                linear_velocity: [0.0, -1.0, 0.0],
+                angular_velocity: [0.0; 3],
+            },
+        ],
+    }
+}
+
+/// A fresh directory for one test, so tests running at the same time don't share files.
+fn temp_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("bevy_vr_blocks_{}_{test}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn saved_scene_loads_blocks() {
+    let dir = temp_dir("saved_scene_loads_blocks");
+    let path = dir.join("blocks.ron");
+    scene().save(&path).unwrap();
+    assert_eq!(BlockScene::load(&path).unwrap(), scene());
+
+    let mut app = headless_app();
+    app.insert_resource(BlockPersistenceSettings {
+        path: path.clone(),
+        autosave: false,
+        load_on_startup: false,
+    });
+    app.add_plugins(BlockPersistencePlugin);
+    app.update();
+    app.world.send_event(LoadBlocks);
+    app.update();
+
+    let mut blocks = app.world.query::<&Block>();
+    let shapes: Vec<_> = blocks.iter(&app.world).map(|block| block.shape).collect();
+    assert_eq!(shapes.len(), 2);
+    assert!(shapes.contains(&ShapeKind::Wedge));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn blocks_are_saved_on_exit() {
+    let dir = temp_dir("blocks_are_saved_on_exit");
+    let path = dir.join("blocks.ron");
+    scene().save(&path).unwrap();
+
+    let mut app = headless_app();
+    app.insert_resource(BlockPersistenceSettings {
+        path: path.clone(),
+        autosave: true,
+        load_on_startup: true,
+    });
+    app.add_plugins(BlockPersistencePlugin);
+    app.update();
+    app.update();
+    std::fs::remove_file(&path).unwrap();
+
+    // Exiting late in the frame still saves before the frame ends
+    app.add_systems(PostUpdate, |mut exit: EventWriter<AppExit>| {
+        exit.send(AppExit);
+    });
+    app.update();
+    assert_eq!(BlockScene::load(&path).unwrap().blocks.len(), 2);
+    std::fs::remove_dir_all(dir).unwrap();
+}
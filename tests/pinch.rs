@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::hands::Hand;
+use bevy_vr_blocks::pinch::{PinchEvent, PinchPhase, PinchPlugin, PinchSettings, PinchState};
+use bevy_vr_blocks::test_support::headless_app;
+use bevy_xr::hands::{HandBone, RightHand};
+
+const CLOSED: f32 = 0.01;
+const BETWEEN: f32 = 0.05;
+const APART: f32 = 0.1;
+
+/// A pinch held since `now`.
+fn held(settings: &PinchSettings) -> PinchState {
+    let mut pinch = PinchState::new(Hand::Right);
+    pinch.update(CLOSED, 0.0, settings);
+    pinch.update(CLOSED, settings.engage_dwell, settings);
+    assert!(pinch.is_held());
+    pinch
+}
+
+#[test]
+fn pinch_engages_after_the_dwell() {
+    let settings = PinchSettings::default();
+    let mut pinch = PinchState::new(Hand::Right);
+    assert_eq!(pinch.update(CLOSED, 1.0, &settings), None);
+    assert_eq!(pinch.phase(), PinchPhase::Pinching);
+    assert_eq!(
+        pinch.update(CLOSED, 1.0 + settings.engage_dwell * 1.5, &settings),
+        Some(PinchEvent::Held(Hand::Right))
+    );
+    assert!(pinch.is_held());
+}
+
+#[test]
+fn pinch_does_not_engage_before_the_dwell() {
+    let settings = PinchSettings::default();
+    let mut pinch = PinchState::new(Hand::Left);
+    pinch.update(CLOSED, 1.0, &settings);
+    assert_eq!(
+        pinch.update(CLOSED, 1.0 + settings.engage_dwell * 0.5, &settings),
+        None
+    );
+    assert_eq!(pinch.phase(), PinchPhase::Pinching);
+
+    // Opening up before the dwell is over starts from scratch
+    pinch.update(APART, 1.0 + settings.engage_dwell * 0.8, &settings);
+    assert_eq!(pinch.phase(), PinchPhase::Open);
+    pinch.update(CLOSED, 1.0 + settings.engage_dwell * 0.9, &settings);
+    assert_eq!(
+        pinch.update(CLOSED, 1.0 + settings.engage_dwell * 1.1, &settings),
+        None
+    );
+    assert_eq!(pinch.phase(), PinchPhase::Pinching);
+}
+
+#[test]
+fn held_pinch_releases_with_hysteresis() {
+    let settings = PinchSettings::default();
+    let mut pinch = held(&settings);
+    let now = settings.engage_dwell;
+
+    // Between the thresholds a held pinch stays held, however long it takes
+    assert_eq!(pinch.update(BETWEEN, now + 1.0, &settings), None);
+    assert!(pinch.is_held());
+
+    // Beyond the release distance it has to stay there for the release dwell
+    assert_eq!(pinch.update(APART, now + 2.0, &settings), None);
+    assert_eq!(
+        pinch.update(BETWEEN, now + 2.0 + settings.release_dwell * 0.5, &settings),
+        None
+    );
+    assert_eq!(
+        pinch.update(APART, now + 2.0 + settings.release_dwell * 0.9, &settings),
+        None
+    );
+    assert!(pinch.is_held());
+    assert_eq!(
+        pinch.update(APART, now + 2.0 + settings.release_dwell * 2.5, &settings),
+        Some(PinchEvent::Released(Hand::Right))
+    );
+    assert_eq!(pinch.phase(), PinchPhase::Released);
+}
+
+#[test]
+fn released_pinch_opens_on_the_next_update() {
+    let settings = PinchSettings::default();
+    let mut pinch = held(&settings);
+    pinch.update(APART, 1.0, &settings);
+    pinch.update(APART, 1.0 + settings.release_dwell * 1.5, &settings);
+    assert_eq!(pinch.phase(), PinchPhase::Released);
+
+    assert_eq!(pinch.update(APART, 1.3, &settings), None);
+    assert_eq!(pinch.phase(), PinchPhase::Open);
+
+    // Closing right away starts a new pinch instead
+    let mut pinch = held(&settings);
+    pinch.update(APART, 1.0, &settings);
+    pinch.update(APART, 1.0 + settings.release_dwell * 1.5, &settings);
+    assert_eq!(pinch.update(CLOSED, 1.3, &settings), None);
+    assert_eq!(pinch.phase(), PinchPhase::Pinching);
+}
+
+#[test]
+fn unlabeled_thumb_tips_do_not_pinch_the_right_hand() {
+    let mut app = headless_app();
+    app.add_plugins(PinchPlugin);
+    let index_tip = Transform::from_xyz(0.0, 1.4, -0.3);
+    let right = app
+        .world
+        .spawn((
+            HandBone::IndexTip,
+            RightHand,
+            SpatialBundle::from_transform(index_tip),
+        ))
+        .id();
+    // A thumb tip that belongs to neither hand, right next to the right index tip
+    app.world
+        .spawn((HandBone::ThumbTip, SpatialBundle::from_transform(index_tip)));
+    for _ in 0..30 {
+        app.update();
+    }
+    let pinch = app.world.get::<PinchState>(right).unwrap();
+    assert_eq!(pinch.phase(), PinchPhase::Open);
+}
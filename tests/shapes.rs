@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use bevy::render::mesh::VertexAttributeValues;
+use bevy_vr_blocks::shapes::ShapeKind;
+
+const SIZE: Vec3 = Vec3::new(0.2, 0.1, 0.3);
+
+/// Volume enclosed by a closed triangle mesh, negative if its faces point inwards.
+fn mesh_volume(mesh: &Mesh) -> f32 {
+    let Some(VertexAttributeValues::Float32x3(positions)) =
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+    else {
+        panic!("mesh should have positions");
+    };
+    let indices: Vec<usize> = mesh
+        .indices()
+        .expect("mesh should be indexed")
+        .iter()
+        .collect();
+    indices
+        .chunks(3)
+        .map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i]]));
+            a.dot(b.cross(c)) / 6.0
+        })
+        .sum()
+}
+
+fn collider_volume(shape: ShapeKind) -> f32 {
+    shape.collider(SIZE).mass_properties(1.0).mass.0
+}
+
+#[test]
+fn wedge_mesh_and_collider_match_its_volume() {
+    let volume = ShapeKind::Wedge.volume(SIZE);
+    assert!((volume - SIZE.x * SIZE.y * SIZE.z / 2.0).abs() < 1e-6);
+    assert!((mesh_volume(&ShapeKind::Wedge.mesh(SIZE)) - volume).abs() < 1e-6);
+    assert!((collider_volume(ShapeKind::Wedge) - volume).abs() < 1e-5);
+}
+
+#[test]
+fn cylinder_mesh_and_collider_match_its_volume() {
+    let volume = ShapeKind::Cylinder.volume(SIZE);
+    let radius = SIZE.x.max(SIZE.z) / 2.0;
+    assert!((volume - std::f32::consts::PI * radius * radius * SIZE.y).abs() < 1e-6);
+    // The mesh is a prism around the circle, so it is slightly smaller
+    let mesh_volume = mesh_volume(&ShapeKind::Cylinder.mesh(SIZE));
+    assert!(
+        mesh_volume > 0.98 * volume && mesh_volume <= volume,
+        "{mesh_volume}"
+    );
+    assert!((collider_volume(ShapeKind::Cylinder) - volume).abs() / volume < 1e-3);
+}
+
+#[test]
+fn meshes_fill_the_box_they_are_made_in() {
+    for shape in [ShapeKind::Cuboid, ShapeKind::Cylinder, ShapeKind::Wedge] {
+        let aabb = shape.mesh(SIZE).compute_aabb().unwrap();
+        let expected = match shape {
+            // Round shapes are as wide as the box in both horizontal directions
+            ShapeKind::Cylinder => Vec3::new(SIZE.z, SIZE.y, SIZE.z) / 2.0,
+            _ => SIZE / 2.0,
+        };
+        assert!(Vec3::from(aabb.center).length() < 1e-5, "{shape:?}");
+        assert!(
+            Vec3::from(aabb.half_extents).distance(expected) < 1e-5,
+            "{shape:?}: {:?}",
+            aabb.half_extents
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use bevy_vr_blocks::layers::Layer;
+use bevy_vr_blocks::shapes::ShapeKind;
+use bevy_vr_blocks::snapping::{BlockPlacement, Placement, SnapSettings, SurfaceSnapSettings};
+use bevy_vr_blocks::test_support::headless_app;
+use bevy_xpbd_3d::prelude::*;
+
+#[test]
+fn snapped_blocks_fill_whole_grid_cells() {
+    let snap = SnapSettings {
+        enabled: true,
+        grid_size: 0.02,
+        angle_step: 15f32.to_radians(),
+    };
+    let transform =
+        Transform::from_xyz(0.113, 1.207, -0.351).with_rotation(Quat::from_rotation_y(0.1));
+    let (snapped, size) = snap.snap(transform, Vec3::new(0.093, 0.041, 0.005));
+
+    assert!(size.abs_diff_eq(Vec3::new(0.1, 0.04, 0.02), 1e-5));
+    assert!(snapped.rotation.abs_diff_eq(Quat::IDENTITY, 1e-5));
+    // Axis aligned, so the faces lie on grid lines
+    let corner = (snapped.translation - size / 2.0) / snap.grid_size;
+    assert!(corner.abs_diff_eq(corner.round(), 1e-3));
+}
+
+#[test]
+fn disabled_snapping_keeps_the_block() {
+    let transform = Transform::from_xyz(0.113, 1.207, -0.351);
+    let size = Vec3::new(0.093, 0.041, 0.005);
+    let (snapped, snapped_size) = SnapSettings::default().snap(transform, size);
+    assert_eq!(snapped, transform);
+    assert_eq!(snapped_size, size);
+}
+
+/// Places a cuboid of `size` at `transform` with surface snapping on.
+fn place_near_surface(app: &mut App, transform: Transform, size: Vec3) -> Placement {
+    app.insert_resource(SnapSettings::default());
+    app.insert_resource(SurfaceSnapSettings {
+        enabled: true,
+        ..default()
+    });
+    // Let the physics pick up the colliders
+    app.update();
+    app.update();
+    let mut placement = SystemState::<BlockPlacement>::new(&mut app.world);
+    placement
+        .get_mut(&mut app.world)
+        .place(ShapeKind::Cuboid, transform, size)
+}
+
+#[test]
+fn blocks_snap_flat_onto_the_floor() {
+    let mut app = headless_app();
+    let floor = app
+        .world
+        .spawn((
+            RigidBody::Static,
+            Collider::cuboid(1.0, 0.002, 1.0),
+            Layer::Floor.collision_layers(),
+            TransformBundle::from_transform(Transform::from_xyz(0.0, 1.0, 0.0)),
+        ))
+        .id();
+    let size = Vec3::splat(0.1);
+    let placement = place_near_surface(&mut app, Transform::from_xyz(0.1, 1.08, 0.0), size);
+
+    let contact = placement.contact.expect("block should snap onto the floor");
+    assert_eq!(contact.entity, floor);
+    assert!(
+        contact.normal.abs_diff_eq(Vec3::Y, 1e-3),
+        "{:?}",
+        contact.normal
+    );
+    assert!(
+        (contact.point.y - 1.001).abs() < 1e-3,
+        "{:?}",
+        contact.point
+    );
+    let bottom = placement.transform.translation.y - size.y / 2.0;
+    assert!((bottom - 1.001).abs() < 1e-3, "{bottom}");
+}
+
+#[test]
+fn blocks_snap_flat_onto_rotated_blocks() {
+    let mut app = headless_app();
+    let center = Vec3::new(0.5, 1.5, 0.0);
+    let rotation = Quat::from_rotation_z(30f32.to_radians());
+    app.world.spawn((
+        RigidBody::Static,
+        Collider::cuboid(0.2, 0.2, 0.2),
+        Layer::Blocks.collision_layers(),
+        TransformBundle::from_transform(
+            Transform::from_translation(center).with_rotation(rotation),
+        ),
+    ));
+    // An upright block just above the tilted top face
+    let up = rotation * Vec3::Y;
+    let size = Vec3::splat(0.1);
+    let corner_depth = 0.05 * (30f32.to_radians().cos() + 30f32.to_radians().sin());
+    let start = Transform::from_translation(center + up * (0.1 + corner_depth + 0.02));
+    let placement = place_near_surface(&mut app, start, size);
+
+    let contact = placement
+        .contact
+        .expect("block should snap onto the other block");
+    assert!(contact.normal.abs_diff_eq(up, 1e-3), "{:?}", contact.normal);
+    assert!(
+        ((contact.point - center).dot(up) - 0.1).abs() < 1e-3,
+        "{:?}",
+        contact.point
+    );
+    let transform = placement.transform;
+    assert!(
+        transform.rotation.angle_between(rotation) < 1e-3,
+        "{:?}",
+        transform.rotation
+    );
+    assert!(
+        transform.translation.abs_diff_eq(center + up * 0.15, 1e-3),
+        "{:?}",
+        transform.translation
+    );
+}
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use bevy_vr_blocks::block_material::BlockMaterial;
+use bevy_vr_blocks::sound_bank::{ImpactSoundBank, Surface};
+use bevy_vr_blocks::test_support::headless_app;
+
+fn sources(bank: &ImpactSoundBank, a: Surface, b: Surface) -> HashSet<AssetId<AudioSource>> {
+    bank.clips(a, b)
+        .iter()
+        .map(|clip| clip.source.id())
+        .collect()
+}
+
+#[test]
+fn every_pair_of_materials_has_its_own_clips() {
+    let mut app = headless_app();
+    let bank = ImpactSoundBank::from_world(&mut app.world);
+    let fallback: HashSet<_> = bank.fallback.iter().map(|clip| clip.source.id()).collect();
+
+    let mut seen = Vec::new();
+    for a in BlockMaterial::ALL {
+        for b in BlockMaterial::ALL {
+            let (a, b) = (Surface::Block(a), Surface::Block(b));
+            let clips = sources(&bank, a, b);
+            // Either order of the pair is the same entry, with takes of both materials
+            assert_eq!(clips, sources(&bank, b, a));
+            assert!(clips.len() >= 3 && clips.is_disjoint(&fallback));
+            if !seen.contains(&clips) {
+                seen.push(clips);
+            }
+        }
+        let block = Surface::Block(a);
+        assert!(sources(&bank, block, Surface::Floor).len() > 3);
+        assert!(sources(&bank, block, Surface::Hand).is_disjoint(&fallback));
+    }
+    // One entry per unordered pair of the five materials
+    assert_eq!(seen.len(), 15);
+}
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use bevy_vr_blocks::test_support::{
+    headless_app, run_for, HandPose, SimulatedHands, SimulatedHandsPlugin, Trajectory,
+};
+use bevy_vr_blocks::widgets::{ButtonClicked, PokeButton, Slider, Widget, WidgetPlugin};
+
+#[derive(Resource, Default)]
+struct Clicks(usize);
+
+fn count_clicks(mut events: EventReader<ButtonClicked>, mut clicks: ResMut<Clicks>) {
+    clicks.0 += events.read().count();
+}
+
+/// Pokes the right index tip from `start` to `end` and back at a button facing +Z.
+fn poke(start: Vec3, end: Vec3) -> usize {
+    let button = Vec3::new(0.0, 1.2, -0.3);
+    let mut app = headless_app();
+    app.add_plugins((SimulatedHandsPlugin, WidgetPlugin));
+    app.init_resource::<Clicks>();
+    app.add_systems(PostUpdate, count_clicks);
+    app.world.spawn((
+        PokeButton,
+        Widget::new(Vec2::new(0.04, 0.02), "Poke"),
+        SpatialBundle::from_transform(Transform::from_translation(button)),
+    ));
+    app.insert_resource(SimulatedHands {
+        left: Trajectory::new(HandPose::open(Vec3::new(-0.5, 1.0, -0.3))),
+        right: Trajectory::new(HandPose::open(button + start))
+            .hold(0.1)
+            .then(0.3, HandPose::open(button + end))
+            .then(0.3, HandPose::open(button + start))
+            .hold(0.1),
+    });
+    run_for(&mut app, 1.0);
+    app.world.resource::<Clicks>().0
+}
+
+#[test]
+fn pushing_a_button_in_clicks_once() {
+    assert_eq!(
+        poke(Vec3::new(0.0, 0.0, 0.02), Vec3::new(0.0, 0.0, -0.008)),
+        1
+    );
+}
+
+#[test]
+fn reaching_through_from_behind_does_not_click() {
+    assert_eq!(
+        poke(Vec3::new(0.0, 0.0, -0.05), Vec3::new(0.0, 0.0, -0.008)),
+        0
+    );
+}
+
+#[test]
+fn slider_values_round_to_steps() {
+    let slider = Slider {
+        value: 1.0,
+        min: 0.0,
+        max: 2.0,
+        step: 0.5,
+    };
+    assert_eq!(slider.fraction(), 0.5);
+    assert_eq!(slider.value_at(0.3), 0.5);
+    assert_eq!(slider.value_at(0.9), 2.0);
+    assert_eq!(slider.value_at(-1.0), 0.0);
+}